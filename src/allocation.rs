@@ -0,0 +1,149 @@
+use clap::ValueEnum;
+
+use crate::candidates::Candidates;
+
+/// Divisor method used to allocate seats between parties.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum Method {
+    /// D'Hondt, with divisors 1, 2, 3, ...
+    Dhondt,
+    /// Sainte-Laguë (Webster), with divisors 1, 3, 5, ...
+    SainteLague,
+}
+
+impl Method {
+    /// Returns the divisor for a party which has already won `won` seats.
+    fn divisor(&self, won : usize) -> u32 {
+        match self {
+            Method::Dhondt => won as u32 + 1,
+            Method::SainteLague => 2 * won as u32 + 1,
+        }
+    }
+}
+
+/// A party, with the combined first preference votes of its candidates.
+#[derive(Debug, Clone)]
+pub struct Party {
+    pub name : String,
+    pub votes : u32,
+}
+
+/// Result of allocating seats between parties.
+#[derive(Debug, Clone)]
+pub struct Allocation {
+    /// Seats won by each party, in the same order as the parties.
+    pub seats : Vec<usize>,
+    /// Divisors applied to the party votes, in the order they were used.
+    pub divisors : Vec<u32>,
+}
+
+/// Sums the votes of each party's candidates. Parties are ordered by their first candidate in the
+/// file, and candidates without a party stand as a party of their own.
+pub fn party_totals(totals : &[u32], candidates : &Candidates) -> Vec<Party> {
+    let mut parties : Vec<Party> = Vec::new();
+
+    for (candidate, &votes) in totals.iter().enumerate() {
+        let party = candidates.party(candidate).unwrap_or_else(|| candidates.get(candidate).unwrap());
+
+        match parties.iter_mut().find(|p| &p.name == party) {
+            Some(existing) => existing.votes += votes,
+            None => parties.push(Party {
+                name : party.clone(),
+                votes,
+            }),
+        }
+    }
+
+    parties
+}
+
+/// Allocates `seats` between the parties using the provided divisor method, awarding each seat in
+/// turn to the party with the highest quotient of votes over the divisor for the seats it already
+/// holds. Equal quotients go to the party with more votes, then to the party listed first.
+pub fn allocate(parties : &[Party], seats : usize, method : Method) -> Allocation {
+    let divisor = |won : usize| method.divisor(won);
+
+    let mut won = vec![0; parties.len()];
+
+    for _ in 0..seats {
+        let mut best : Option<usize> = None;
+
+        for (party, details) in parties.iter().enumerate() {
+            best = match best {
+                None => Some(party),
+                Some(current) => {
+                    // Compare the quotients by cross multiplying to avoid rounding.
+                    let challenger = u64::from(details.votes) * u64::from(divisor(won[current]));
+                    let incumbent = u64::from(parties[current].votes) * u64::from(divisor(won[party]));
+
+                    if challenger > incumbent || (challenger == incumbent && details.votes > parties[current].votes) {
+                        Some(party)
+                    }
+                    else {
+                        Some(current)
+                    }
+                }
+            };
+        }
+
+        if let Some(best) = best {
+            won[best] += 1;
+        }
+    }
+
+    let most_won = won.iter().copied().max().unwrap_or(0);
+
+    Allocation {
+        divisors : (0..most_won).map(divisor).collect(),
+        seats : won,
+    }
+}
+
+/// Computes the Gallagher least squares index of disproportionality between each party's share of
+/// the votes and its share of the seats, as a percentage.
+pub fn gallagher(parties : &[Party], allocation : &Allocation) -> f64 {
+    let total_votes : u32 = parties.iter().map(|p| p.votes).sum();
+    let total_seats : usize = allocation.seats.iter().sum();
+
+    if total_votes == 0 || total_seats == 0 {
+        return 0.0;
+    }
+
+    let sum_of_squares : f64 =
+        parties
+        .iter()
+        .zip(&allocation.seats)
+        .map(|(party, &seats)| {
+            let vote_share = 100.0 * f64::from(party.votes) / f64::from(total_votes);
+            let seat_share = 100.0 * seats as f64 / total_seats as f64;
+            (vote_share - seat_share).powi(2)
+        })
+        .sum();
+
+    (sum_of_squares / 2.0).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parties() -> Vec<Party> {
+        [("A", 53_000), ("B", 24_000), ("C", 23_000)]
+        .into_iter()
+        .map(|(name, votes)| Party { name : String::from(name), votes })
+        .collect()
+    }
+
+    #[test]
+    fn dhondt_favours_the_largest_party() {
+        assert_eq!(allocate(&parties(), 7, Method::Dhondt).seats, vec![4, 2, 1]);
+    }
+
+    #[test]
+    fn sainte_lague_favours_smaller_parties() {
+        let allocation = allocate(&parties(), 7, Method::SainteLague);
+
+        assert_eq!(allocation.seats, vec![3, 2, 2]);
+        assert_eq!(allocation.divisors, vec![1, 3, 5]);
+    }
+}
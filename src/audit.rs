@@ -0,0 +1,70 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::ballot_box::{
+    CountStatus,
+    CountStatus::*
+};
+use crate::candidates::Candidates;
+use crate::tiebreak::Tiebreak;
+
+/// Append-only log of each decision made during a count, intended for certifying results.
+///
+/// Each line is `<unix time> <event> <key>=<value>...`, where lists of candidates are given as
+/// comma separated indices into the candidates listed by the `start` event. Together with the
+/// totals of each round this is enough to independently reconstruct the count.
+pub struct AuditLog {
+    file : fs::File,
+}
+
+impl AuditLog {
+    /// Opens the log at `path`, creating it if it doesn't exist and appending otherwise.
+    pub fn open(path : &path::Path) -> io::Result<Self> {
+        let file =
+            fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+
+        Ok(AuditLog {
+            file,
+        })
+    }
+
+    /// Writes a single timestamped line to the log.
+    fn write(&mut self, event : &str, fields : &str) -> io::Result<()> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+
+        writeln!(self.file, "{}.{:03} {} {}", now.as_secs(), now.subsec_millis(), event, fields)
+    }
+
+    /// Records the start of a count, along with the inputs which determine its outcome.
+    pub fn start(&mut self, path : &path::Path, threshold : f64, tiebreak : Tiebreak, seed : u64, candidates : &Candidates) -> io::Result<()> {
+        let names : Vec<&String> = (0..candidates.len()).map(|c| candidates.get(c).unwrap()).collect();
+
+        self.write("start", &format!("path={:?} threshold={} tiebreak={:?} seed={} candidates={:?}", path, threshold, tiebreak, seed, names))
+    }
+
+    /// Records the totals of a round and the decision made from them.
+    pub fn round(&mut self, round : usize, totals : &[u32], status : &CountStatus) -> io::Result<()> {
+        let decision = match status {
+            Winner(winner) => format!("winner candidates={}", winner),
+            Tie(tied) => format!("tie candidates={}", join(tied)),
+            Runoff(to_eliminate) => format!("runoff candidates={}", join(to_eliminate)),
+            Promotion(to_promote) => format!("promotion candidates={}", join(to_promote)),
+        };
+
+        self.write("round", &format!("round={} totals={} decision={}", round, join(totals), decision))
+    }
+}
+
+/// Joins a list of values with commas.
+fn join<T : ToString>(values : &[T]) -> String {
+    values
+    .iter()
+    .map(|v| v.to_string())
+    .collect::<Vec<String>>()
+    .join(",")
+}
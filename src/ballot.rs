@@ -1,4 +1,131 @@
 use std::collections::HashSet;
+use std::num::NonZeroUsize;
+
+use clap::ValueEnum;
+
+use crate::encoding::Encoding;
+
+/// How the preferences in each cell of the file are written.
+#[derive(Debug, Clone, Copy, Default, ValueEnum)]
+pub enum PreferenceFormat {
+    /// Bare numbers (1, 2, 3).
+    #[default]
+    Number,
+    /// Numbers with an ordinal suffix (1st, 2nd, 3rd), as well as bare numbers.
+    Ordinal,
+    /// Letters (A, B, C), where A is the first preference. A letter past the number of
+    /// candidates makes the ballot invalid.
+    Letter,
+}
+
+impl PreferenceFormat {
+    /// Reads the preference expressed in a cell, or `None` if the cell doesn't express one.
+    pub fn parse(&self, cell : &str) -> Option<usize> {
+        // Files edited on Windows can leave a stray `\r` on the last cell of a row, and some
+        // exports pad cells with spaces.
+        let cell = cell.trim();
+
+        match self {
+            PreferenceFormat::Number => cell.parse::<usize>().ok(),
+            PreferenceFormat::Ordinal => {
+                let cell = cell.to_lowercase();
+
+                let number =
+                    ["st", "nd", "rd", "th"]
+                    .iter()
+                    .find_map(|suffix| cell.strip_suffix(suffix))
+                    .unwrap_or(&cell);
+
+                number.parse::<usize>().ok()
+            }
+            PreferenceFormat::Letter => {
+                let mut chars = cell.chars();
+
+                match (chars.next(), chars.next()) {
+                    (Some(letter), None) if letter.is_ascii_alphabetic() => Some(usize::from(letter.to_ascii_uppercase() as u8 - b'A') + 1),
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    /// Returns whether preferences must be no greater than the number of candidates.
+    fn bounded(&self) -> bool {
+        matches!(self, PreferenceFormat::Letter)
+    }
+}
+
+/// What a column of a ballot file holds, when reading a row of preferences.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnKind {
+    /// A candidate in the count.
+    Candidate,
+    /// A candidate removed from the count, whose preferences are skipped over.
+    Dropped,
+    /// Something other than preferences, such as the ID of each ballot.
+    Other,
+}
+
+/// How to read a ballot which gives the same preference to more than one candidate.
+#[derive(Debug, Clone, Copy, Default, ValueEnum)]
+pub enum EqualRanks {
+    /// The whole ballot is invalid.
+    #[default]
+    Discard,
+    /// The ballot is counted up to the preference before the first one that was repeated.
+    Truncate,
+}
+
+/// How the ballots are laid out in the file.
+#[derive(Debug, Clone, Copy, Default, ValueEnum)]
+pub enum InputFormat {
+    /// One column for each candidate, holding the preference given to them.
+    #[default]
+    Columns,
+    /// Candidate indices (starting from 0) listed in order of preference, so `2,0,1` puts the
+    /// third candidate first.
+    Ordered,
+}
+
+/// Rules applied when reading a ballot from the file.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Rules {
+    /// Only the first this many preferences on a ballot are counted.
+    pub max_preferences : Option<NonZeroUsize>,
+    /// Ballots expressing fewer than this many preferences are invalid.
+    pub min_preferences : usize,
+    /// How the preferences in each cell are written.
+    pub format : PreferenceFormat,
+    /// Encoding of files without a byte order mark.
+    pub encoding : Encoding,
+    /// How to read ballots which repeat a preference.
+    pub equal_ranks : EqualRanks,
+    /// How the ballots are laid out in the file.
+    pub input : InputFormat,
+    /// Whether to count rows which exactly repeat an earlier row in the file.
+    pub find_duplicates : bool,
+    /// Files with more candidates than this are rejected before any ballots are read.
+    pub max_candidates : Option<usize>,
+}
+
+/// Reasons a ballot can be invalid.
+#[derive(Debug, Clone, Copy)]
+pub enum InvalidReason {
+    /// The same preference was expressed for more than one candidate.
+    RepeatedPreference,
+    /// No preference was expressed at all.
+    NoPreferences,
+    /// Fewer preferences were expressed than the minimum required.
+    TooFewPreferences(usize),
+    /// A preference was past the number of candidates, in a format where that isn't allowed.
+    PreferenceOutOfRange,
+    /// The ballot lists more candidates than there are.
+    TooManyPreferences(usize),
+    /// The ballot lists a candidate who doesn't exist.
+    UnknownCandidate,
+    /// Every preference on the ballot was for a candidate who was dropped before counting.
+    OnlyDroppedCandidates,
+}
 
 /// Represents a ballot paper.
 #[derive(Debug, Clone)]
@@ -16,18 +143,43 @@ impl Ballot {
         self.0.iter()
     }
 
-    /// Removes the specified candidates from the ballot.
-    pub fn remove_candidates(ballot : Ballot, to_remove : &[usize]) -> Option<Ballot> {
-        let new_ballot: Vec<_> = 
+    /// Removes the leading preferences for the specified candidates from the ballot, returning what
+    /// is left, if anything, along with how many preferences were removed. Later preferences for
+    /// the candidates are kept, so each preference stays the same distance from the first.
+    pub fn skip_candidates(ballot : Ballot, to_skip : &[usize]) -> Option<(Ballot, usize)> {
+        let skipped =
             ballot.0
-            .into_iter()
-            .filter(|c| !to_remove.contains(c))
-            .collect();
+            .iter()
+            .position(|c| !to_skip.contains(c))?;
+
+        let mut new_ballot = ballot.0;
+        new_ballot.drain(..skipped);
+
+        Some((Ballot::new(new_ballot), skipped))
+    }
+
+    /// Checks that the ballot can be counted among `candidates` candidates, which ballots read from
+    /// a file always can be, but those built in memory may not. A ballot must list at least one
+    /// candidate, and no candidate more than once.
+    pub fn check(&self, candidates : usize) -> Result<(), InvalidReason> {
+        if self.0.is_empty() {
+            return Err(InvalidReason::NoPreferences);
+        }
 
-        match new_ballot.len() {
-            0 => None,
-            _ => Some(Ballot::new(new_ballot))
+        if self.0.len() > candidates {
+            return Err(InvalidReason::TooManyPreferences(candidates));
         }
+
+        let mut listed = vec![false; candidates];
+        for &candidate in &self.0 {
+            match listed.get_mut(candidate) {
+                None => return Err(InvalidReason::UnknownCandidate),
+                Some(true) => return Err(InvalidReason::RepeatedPreference),
+                Some(listed) => *listed = true,
+            }
+        }
+
+        Ok(())
     }
 
     /// Returns the highest preference candidate.
@@ -35,29 +187,144 @@ impl Ballot {
         self.0[0]
     }
 
-    /// Creates a ballot from the representation read from the file.
-    pub fn from_raw_ballot(raw_ballot : Vec<Option<usize>>) -> Result<Ballot, Vec<Option<usize>>> {
+    /// Creates a ballot from the cells of a row of the file, with one cell for each candidate in
+    /// order, applying the provided rules.
+    pub fn parse_row<'a>(cells : impl IntoIterator<Item = &'a str>, rules : Rules) -> Result<Ballot, (Vec<Option<usize>>, InvalidReason)> {
+        let raw_ballot =
+            cells
+            .into_iter()
+            .map(|cell| rules.format.parse(cell))
+            .collect();
+
+        Ballot::from_raw_ballot(raw_ballot, rules)
+    }
+
+    /// Creates a ballot from the cells of a row of the file like `parse_row`, but only keeping the
+    /// cells whose column `kinds` marks as a candidate. Preferences are still checked against every
+    /// candidate column, including dropped ones, and a ballot whose only preferences were in
+    /// dropped columns is invalid. Other columns are ignored entirely.
+    pub fn parse_row_keeping<'a>(cells : impl IntoIterator<Item = &'a str>, kinds : &[ColumnKind], rules : Rules) -> Result<Ballot, (Vec<Option<usize>>, InvalidReason)> {
+        let row : Vec<(Option<usize>, ColumnKind)> =
+            cells
+            .into_iter()
+            .zip(kinds)
+            .filter(|(_, &kind)| kind != ColumnKind::Other)
+            .map(|(cell, &kind)| (rules.format.parse(cell), kind))
+            .collect();
+
+        let raw_ballot =
+            row
+            .iter()
+            .filter(|(_, kind)| *kind == ColumnKind::Candidate)
+            .map(|(preference, _)| *preference)
+            .collect();
+
+        let any_dropped = row.iter().any(|(preference, kind)| *kind == ColumnKind::Dropped && preference.is_some());
+
+        match Ballot::from_raw_ballot_within(raw_ballot, row.len(), rules) {
+            Err((raw_ballot, InvalidReason::NoPreferences)) if any_dropped => Err((raw_ballot, InvalidReason::OnlyDroppedCandidates)),
+            parsed => parsed,
+        }
+    }
+
+    /// Creates a ballot from the cells of a row listing candidate indices in order of preference,
+    /// applying the provided rules. `columns` maps each candidate index in the file to its index
+    /// in the count, or to `None` if that candidate was dropped.
+    pub fn parse_ordered<'a>(cells : impl IntoIterator<Item = &'a str>, columns : &[Option<usize>], rules : Rules) -> Result<Ballot, (Vec<Option<usize>>, InvalidReason)> {
+        let raw_ballot : Vec<Option<usize>> =
+            cells
+            .into_iter()
+            .map(|cell| PreferenceFormat::Number.parse(cell))
+            .collect();
+
+        let listed : Vec<usize> = raw_ballot.iter().flatten().copied().collect();
+        let any_listed = !listed.is_empty();
+
+        let mut ballot = Vec::with_capacity(listed.len());
+
+        for candidate in listed {
+            match columns.get(candidate) {
+                None => return Err((raw_ballot, InvalidReason::UnknownCandidate)),
+                // Dropped candidates are skipped as though they were never listed.
+                Some(None) => (),
+                Some(Some(candidate)) => {
+                    if ballot.contains(candidate) {
+                        return Err((raw_ballot, InvalidReason::RepeatedPreference));
+                    }
+                    ballot.push(*candidate);
+                }
+            }
+        }
+
+        match ballot.len() {
+            0 if any_listed => Err((raw_ballot, InvalidReason::OnlyDroppedCandidates)),
+            0 => Err((raw_ballot, InvalidReason::NoPreferences)),
+            n if n < rules.min_preferences => Err((raw_ballot, InvalidReason::TooFewPreferences(rules.min_preferences))),
+            _ => {
+                if let Some(max_preferences) = rules.max_preferences {
+                    ballot.truncate(max_preferences.get());
+                }
+
+                Ok(Ballot::new(ballot))
+            }
+        }
+    }
+
+    /// Creates a ballot from the representation read from the file, applying the provided rules.
+    /// The ballot is validated as written before any preferences beyond `max_preferences` are
+    /// dropped.
+    pub fn from_raw_ballot(raw_ballot : Vec<Option<usize>>, rules : Rules) -> Result<Ballot, (Vec<Option<usize>>, InvalidReason)> {
+        let width = raw_ballot.len();
+        Ballot::from_raw_ballot_within(raw_ballot, width, rules)
+    }
+
+    /// Helper function for `from_raw_ballot`, where `width` is the number of candidates that
+    /// bounded preference formats are checked against.
+    fn from_raw_ballot_within(raw_ballot : Vec<Option<usize>>, width : usize, rules : Rules) -> Result<Ballot, (Vec<Option<usize>>, InvalidReason)> {
         let mut pref_pairs = Vec::with_capacity(raw_ballot.len());
 
         let mut preference_set = HashSet::with_capacity(raw_ballot.len());
 
+        // Lowest preference which was expressed more than once.
+        let mut repeated : Option<usize> = None;
+
         for (candidate, preference) in raw_ballot.iter().enumerate() {
             if let Some(preference) = preference {
                 if !preference_set.insert(preference) {
                     // Value already existed in set, which means preference was expressed twice.
-                    return Err(raw_ballot);
+                    match rules.equal_ranks {
+                        EqualRanks::Discard => return Err((raw_ballot, InvalidReason::RepeatedPreference)),
+                        EqualRanks::Truncate => repeated = Some(repeated.map_or(*preference, |r| r.min(*preference))),
+                    }
+                }
+                if rules.format.bounded() && *preference > width {
+                    return Err((raw_ballot, InvalidReason::PreferenceOutOfRange));
                 }
                 pref_pairs.push((preference, candidate));
             }
         }
 
+        // Only the preferences before the first repeated one are unambiguous.
+        if let Some(repeated) = repeated {
+            pref_pairs.retain(|(preference, _)| **preference < repeated);
+        }
+
         match pref_pairs.len() {
+            // The first preference itself was repeated, so nothing is left.
+            0 if repeated.is_some() => Err((raw_ballot, InvalidReason::RepeatedPreference)),
             // No preference was expressed at all.
-            0 => Err(raw_ballot),
+            0 => Err((raw_ballot, InvalidReason::NoPreferences)),
+            // Not enough preferences were expressed to meet the minimum.
+            n if n < rules.min_preferences => Err((raw_ballot, InvalidReason::TooFewPreferences(rules.min_preferences))),
             _ => {
                 // Sort the ballot by order of preference.
                 pref_pairs.sort_by(|(p1, _), (p2, _)| p1.cmp(p2));
 
+                // Preferences past the maximum are treated as never having been expressed.
+                if let Some(max_preferences) = rules.max_preferences {
+                    pref_pairs.truncate(max_preferences.get());
+                }
+
                 // Resolve the preference-candidate pairs to just the candidate.
                 let ballot =
                     pref_pairs
@@ -70,3 +337,84 @@ impl Ballot {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_accepts_a_ballot_listing_candidates_once() {
+        assert!(Ballot::new(vec![2, 0]).check(3).is_ok());
+    }
+
+    #[test]
+    fn check_rejects_an_empty_ballot() {
+        assert!(matches!(Ballot::new(vec![]).check(3), Err(InvalidReason::NoPreferences)));
+    }
+
+    #[test]
+    fn check_rejects_a_ballot_longer_than_the_candidates() {
+        assert!(matches!(Ballot::new(vec![0, 1, 2, 3]).check(3), Err(InvalidReason::TooManyPreferences(3))));
+    }
+
+    #[test]
+    fn check_rejects_an_unknown_candidate() {
+        assert!(matches!(Ballot::new(vec![0, 3]).check(3), Err(InvalidReason::UnknownCandidate)));
+    }
+
+    #[test]
+    fn check_rejects_a_repeated_candidate() {
+        assert!(matches!(Ballot::new(vec![1, 0, 1]).check(3), Err(InvalidReason::RepeatedPreference)));
+    }
+
+    #[test]
+    fn parse_row_keeping_checks_letters_against_candidate_columns_only() {
+        use ColumnKind::*;
+        let rules = Rules { format : PreferenceFormat::Letter, ..Rules::default() };
+
+        let parsed = Ballot::parse_row_keeping(["101", "A", "B", "D"], &[Other, Candidate, Candidate, Candidate], rules);
+
+        assert!(matches!(parsed, Err((_, InvalidReason::PreferenceOutOfRange))));
+    }
+
+    #[test]
+    fn parse_row_keeping_reads_a_blank_row_beside_an_id_as_having_no_preferences() {
+        use ColumnKind::*;
+
+        let parsed = Ballot::parse_row_keeping(["102", "", "", ""], &[Other, Candidate, Candidate, Candidate], Rules::default());
+
+        assert!(matches!(parsed, Err((_, InvalidReason::NoPreferences))));
+    }
+
+    #[test]
+    fn parse_row_keeping_rejects_a_row_with_only_dropped_preferences() {
+        use ColumnKind::*;
+
+        let parsed = Ballot::parse_row_keeping(["102", "1", "", ""], &[Other, Dropped, Candidate, Candidate], Rules::default());
+
+        assert!(matches!(parsed, Err((_, InvalidReason::OnlyDroppedCandidates))));
+    }
+
+    #[test]
+    fn letters_are_read_as_preferences_from_a() {
+        assert_eq!(PreferenceFormat::Letter.parse("A"), Some(1));
+        assert_eq!(PreferenceFormat::Letter.parse(" c "), Some(3));
+        assert_eq!(PreferenceFormat::Letter.parse("AB"), None);
+        assert_eq!(PreferenceFormat::Letter.parse("1"), None);
+    }
+
+    #[test]
+    fn truncate_keeps_only_the_preferences_before_an_equal_rank() {
+        let rules = Rules { equal_ranks : EqualRanks::Truncate, ..Rules::default() };
+
+        // B and C share the second preference, so only A's first preference is unambiguous.
+        let ballot = Ballot::parse_row(["1", "2", "2", "3"], rules).unwrap();
+
+        assert_eq!(ballot.iter().copied().collect::<Vec<usize>>(), vec![0]);
+    }
+
+    #[test]
+    fn discard_rejects_a_ballot_with_an_equal_rank() {
+        assert!(matches!(Ballot::parse_row(["1", "2", "2", "3"], Rules::default()), Err((_, InvalidReason::RepeatedPreference))));
+    }
+}
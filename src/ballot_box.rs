@@ -1,19 +1,42 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io::Read;
 use std::mem;
 use std::path;
 
 use crate::candidates::Candidates;
+use crate::encoding::Encoding;
+use crate::input;
+use crate::election::{self, CountConfig, Rounds};
+use crate::error::Error;
+use crate::filter::Filter;
+use crate::formatting::Formatter;
+use crate::random::Rng;
+use crate::tiebreak::Tiebreak;
 use crate::reporting;
-use crate::ballot::Ballot;
+use crate::ballot::{Ballot, ColumnKind, InputFormat, InvalidReason, Rules};
 
-/// Represents the current status of the count, and how to proceed counting.
-#[derive(Clone, Debug)]
+/// Represents the current status of the count, and how to proceed counting. Candidates in each
+/// list are always in the order they appear in the file, so output is the same between runs.
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum CountStatus {
     Winner(usize),
-    Tie,
+    /// Every remaining vote has been exhausted, leaving the candidates still in the count tied.
+    Tie(Vec<usize>),
     Promotion(Vec<usize>),
     Runoff(Vec<usize>),
 }
 
+impl CountStatus {
+    /// Returns the candidates listed in the status, if any.
+    fn listed(&self) -> &[usize] {
+        match self {
+            CountStatus::Winner(_) => &[],
+            CountStatus::Tie(candidates) | CountStatus::Promotion(candidates) | CountStatus::Runoff(candidates) => candidates,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 /// Node of trie like structure representing the votes. This stores ballots with common starting
 /// preference, using the endings value to count how many votes expressed the same preference from
@@ -22,6 +45,9 @@ pub enum CountStatus {
 struct BallotBoxNode {
     total_beneath : u32,
     endings : u32,
+    /// Splits `endings` by how many preferences the votes had before their first in the trie, which
+    /// is only ever more than none once they have been transferred.
+    endings_by_start : Vec<u32>,
     children : Vec<Option<BallotBoxNode>>,
 }
 
@@ -31,9 +57,125 @@ impl BallotBoxNode {
         BallotBoxNode {
             total_beneath : 0,
             endings : 0,
+            endings_by_start : Vec::new(),
             children : vec![None; children],
         }
     }
+
+    /// Adds the votes in another node, and everything beneath it, into this node.
+    fn merge(&mut self, other : BallotBoxNode) {
+        self.total_beneath += other.total_beneath;
+        self.endings += other.endings;
+
+        if self.endings_by_start.len() < other.endings_by_start.len() {
+            self.endings_by_start.resize(other.endings_by_start.len(), 0);
+        }
+        for (endings, other_endings) in self.endings_by_start.iter_mut().zip(other.endings_by_start) {
+            *endings += other_endings;
+        }
+
+        for (child, other_child) in self.children.iter_mut().zip(other.children) {
+            BallotBoxNode::merge_into(child, other_child);
+        }
+    }
+
+    /// Returns the number of votes ending exactly `depth` levels below this node, where this node
+    /// is at a depth of 1.
+    fn endings_at_depth(&self, depth : usize) -> u32 {
+        match depth {
+            0 => 0,
+            1 => self.endings,
+            _ => {
+                self
+                .children
+                .iter()
+                .flatten()
+                .map(|child| child.endings_at_depth(depth - 1))
+                .sum()
+            }
+        }
+    }
+
+    /// Returns the number of votes beneath this node which only express preferences for candidates
+    /// in `gone`, where this node's own candidate is assumed to be in it.
+    fn endings_within(&self, gone : &[bool]) -> u32 {
+        let below : u32 =
+            self
+            .children
+            .iter()
+            .enumerate()
+            .filter(|(candidate, _)| gone[*candidate])
+            .filter_map(|(_, child)| child.as_ref())
+            .map(|child| child.endings_within(gone))
+            .sum();
+
+        self.endings + below
+    }
+
+    /// Adds the votes beneath this node to a pairwise preference matrix, where `path` holds the
+    /// preferences leading to and including this node.
+    fn add_pairwise(&self, path : &mut Vec<usize>, matrix : &mut [Vec<u32>]) {
+        if self.endings > 0 {
+            for (rank, &preferred) in path.iter().enumerate() {
+                // Every candidate ranked later, or not at all, is beaten by this preference.
+                for (other, votes) in matrix[preferred].iter_mut().enumerate() {
+                    if other != preferred && !path[..rank].contains(&other) {
+                        *votes += self.endings;
+                    }
+                }
+            }
+        }
+
+        for (candidate, child) in self.children.iter().enumerate() {
+            if let Some(child) = child {
+                path.push(candidate);
+                child.add_pairwise(path, matrix);
+                path.pop();
+            }
+        }
+    }
+
+    /// Returns whether any vote beneath this node goes on to express a preference for one of the
+    /// `wanted` candidates.
+    fn reaches(&self, wanted : &[bool]) -> bool {
+        self
+        .children
+        .iter()
+        .enumerate()
+        .any(|(candidate, child)| child.as_ref().is_some_and(|child| wanted[candidate] || child.reaches(wanted)))
+    }
+
+    /// Merges an optional node into another, taking it as is if there was nothing there before.
+    fn merge_into(node : &mut Option<BallotBoxNode>, other : Option<BallotBoxNode>) {
+        match (node.as_mut(), other) {
+            (_, None) => (),
+            (None, Some(other)) => *node = Some(other),
+            (Some(node), Some(other)) => node.merge(other),
+        }
+    }
+}
+
+/// Selects the rows of a file stacking several contests which belong to one of them.
+#[derive(Debug, Clone, Copy)]
+pub struct Contest<'a> {
+    /// Header of the column naming the contest of each row.
+    pub column : &'a str,
+    /// Name of the contest whose rows are read.
+    pub name : &'a str,
+}
+
+/// Columns of a ballot file which don't hold preferences for a candidate.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Columns<'a> {
+    /// Headers of candidates whose columns are ignored entirely, as though they were never in the
+    /// file.
+    pub dropped : &'a [String],
+    /// Contest whose rows are read, if the file holds several.
+    pub contest : Option<Contest<'a>>,
+    /// Header of the column marking a formal abstention.
+    pub abstain : Option<&'a str>,
+    /// Header, or index from 0, of the column holding each ballot's ID.
+    pub id : Option<&'a str>,
 }
 
 /// Stores list of candidates, total number of votes, the candidates which have been eliminated and
@@ -42,63 +184,307 @@ impl BallotBoxNode {
 pub struct BallotBox {
     eliminated : Vec<bool>,
     total_votes : u32,
+    ballots : u32,
+    exhausted : u32,
+    transfer_levels : Vec<u32>,
+    duplicate_rows : u32,
+    abstentions : u32,
+    filtered : u32,
     nodes : Vec<Option<BallotBoxNode>>,
+    /// Copy of the ballot box from the last checkpoint, kept so that the count can be reset.
+    initial : Option<Box<BallotBox>>,
+    /// Totals of each candidate before the last candidates were eliminated or promoted.
+    previous_totals : Option<Vec<u32>>,
     pub candidates : Candidates,
 }
 
 impl BallotBox {
-    /// Creates a new, empty ballot box.
+    /// Creates a new, empty ballot box. Every candidate starts out eliminated, and is only brought
+    /// back into the count when a ballot gives them a first preference, so candidates who never
+    /// receive a first preference are eliminated from the start and their later preferences are
+    /// skipped over in every transfer.
     fn new(candidates : Candidates) -> Self {
         BallotBox {
             eliminated : vec![true; candidates.len()],
             total_votes : 0,
+            ballots : 0,
+            exhausted : 0,
+            transfer_levels : Vec::new(),
+            duplicate_rows : 0,
+            abstentions : 0,
+            filtered : 0,
             nodes : vec![None; candidates.len()],
+            initial : None,
+            previous_totals : None,
             candidates,
         }
     }
 
-    /// Reads and fills the ballot box from a file.
-    pub fn from_file(path : &path::PathBuf, report : bool) -> Result<BallotBox, csv::Error> {
+    /// Creates a ballot box filled with the provided ballots, failing on the first ballot which
+    /// can't be counted among the candidates.
+    pub fn from_ballots(candidates : Candidates, ballots : Vec<Ballot>) -> Result<Self, InvalidReason> {
+        let mut ballot_box = BallotBox::new(candidates);
+        ballot_box.add_ballots(ballots)?;
+
+        Ok(ballot_box)
+    }
+
+    /// Adds more ballots to the ballot box, such as the next batch of a count still coming in. If
+    /// any ballot can't be counted among the candidates none of them are added. The ballot box is
+    /// reset first, so the ballots are counted from the first round, and any checkpoint is taken
+    /// again to include them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if counting has started on a ballot box without a checkpoint to reset it to.
+    pub fn add_ballots(&mut self, ballots : Vec<Ballot>) -> Result<(), InvalidReason> {
+        for ballot in &ballots {
+            ballot.check(self.candidates.len())?;
+        }
+
+        self.reset();
+        assert!(!self.counting_started(), "ballots can't be added to a count under way without a checkpoint");
+
+        let checkpointed = self.initial.take().is_some();
+
+        for ballot in ballots {
+            self.push(ballot, 1)?;
+            self.ballots += 1;
+        }
+
+        if checkpointed {
+            self.checkpoint();
+        }
+
+        Ok(())
+    }
+
+    /// Counts a copy of the ballot box to completion, needing `threshold` of the votes to win, and
+    /// returns the winner, or `None` for a tie. The ballot box itself is left as it was, so this can
+    /// be called after each batch of `add_ballots` for a provisional result.
+    pub fn provisional_result(&self, threshold : f64) -> Option<usize> {
+        election::run_election(&mut self.clone(), &CountConfig::new().threshold(threshold), None)
+    }
+
+    /// Reads and fills the ballot box from a file, reading each ballot according to `rules`. None
+    /// of the special `columns` are read as candidates. If a contest is provided, only its rows are
+    /// read. A row with no preferences which marks the abstain column is recorded as an abstention
+    /// rather than an invalid ballot, and invalid ballots are reported by their ID if there is an ID
+    /// column. Valid ballots which don't meet every one of `filters` are left out of the count.
+    pub fn from_file(path : &path::Path, rules : Rules, columns : Columns, filters : &[Filter], formatter : &Formatter, report : bool) -> Result<BallotBox, Error> {
+        BallotBox::from_reader(fs::File::open(path)?, rules, columns, filters, formatter, report)
+    }
+
+    /// Reads and fills the ballot box like `from_file`, but from the contents of a ballot file
+    /// which has already been opened or read.
+    pub fn from_reader(reader : impl Read + 'static, rules : Rules, columns : Columns, filters : &[Filter], formatter : &Formatter, report : bool) -> Result<BallotBox, Error> {
+        let contents = input::utf8_reader(reader, rules.encoding)?;
 
         let mut reader =
             csv::ReaderBuilder::new()
             .has_headers(true)
-            .from_path(path)?;
+            .flexible(matches!(rules.input, InputFormat::Ordered))
+            .from_reader(contents);
 
         // Read the headers and create the candidates.
         let headers = reader.headers()?;
 
+        // Column holding the contest of each row, which is never a candidate.
+        let contest = match columns.contest {
+            Some(contest) => Some((column_index(headers, contest.column)?, contest.name)),
+            None => None,
+        };
+
+        // Column marking a formal abstention, which is never a candidate either.
+        let abstain = match columns.abstain {
+            Some(abstain) => Some(column_index(headers, abstain)?),
+            None => None,
+        };
+
+        // Column holding each ballot's ID, named either by its header or by its index.
+        let id = match columns.id {
+            Some(id) => Some(column_index(headers, id).or_else(|error| id.parse::<usize>().ok().filter(|&i| i < headers.len()).ok_or(error))?),
+            None => None,
+        };
+
+        // Columns of candidates removed from the file. A misspelt header would otherwise leave the
+        // candidate in the count.
+        let dropped : Vec<usize> =
+            columns.dropped
+            .iter()
+            .map(|header| column_index(headers, header))
+            .collect::<Result<_, _>>()?;
+
+        // Columns holding something other than preferences, which are never part of a ballot.
+        let special : Vec<bool> =
+            (0..headers.len())
+            .map(|column| contest.is_some_and(|(c, _)| c == column) || abstain == Some(column) || id == Some(column))
+            .collect();
+
+        let kept : Vec<bool> =
+            special
+            .iter()
+            .enumerate()
+            .map(|(column, &special)| !special && !dropped.contains(&column))
+            .collect();
+
+        let kinds : Vec<ColumnKind> =
+            special
+            .iter()
+            .zip(&kept)
+            .map(|(&special, &keep)| match (special, keep) {
+                (true, _) => ColumnKind::Other,
+                (false, true) => ColumnKind::Candidate,
+                (false, false) => ColumnKind::Dropped,
+            })
+            .collect();
+
         let candidates : Vec<String> =
             headers
             .into_iter()
-            .map(|x| (*x).parse::<String>())
+            .zip(&kept)
+            .filter(|(_, &keep)| keep)
+            .map(|(x, _)| (*x).parse::<String>())
             .map(|x| x.unwrap())
             .collect();
 
+        // Index in the count of each candidate in the file, which ordered ballots number from 0
+        // skipping the special columns, or `None` if the candidate was dropped.
+        let candidate_columns : Vec<Option<usize>> =
+            kept
+            .iter()
+            .zip(&special)
+            .filter(|(_, &special)| !special)
+            .scan(0, |next, (&keep, _)| {
+                let index = keep.then_some(*next);
+                *next += usize::from(keep);
+                Some(index)
+            })
+            .collect();
+
+        // Each preference on a ballot may allocate a slot for every candidate, so a malformed file
+        // with thousands of columns could run out of memory.
+        if let Some(max) = rules.max_candidates.filter(|&max| candidates.len() > max) {
+            return Err(Error::TooManyCandidates(candidates.len(), max));
+        }
+
         let candidates = Candidates::new(candidates);
 
+        // Each filter along with the index of the candidate it names.
+        let filters : Vec<(usize, &Filter)> =
+            filters
+            .iter()
+            .map(|filter| match candidates.index(filter.candidate()) {
+                Some(candidate) => Ok((candidate, filter)),
+                None => Err(Error::MissingColumn(String::from(filter.candidate()))),
+            })
+            .collect::<Result<_, _>>()?;
+
         let mut ballot_box = BallotBox::new(candidates);
 
+        // Every row seen so far, if duplicates are being looked for.
+        let mut seen : HashSet<Vec<String>> = HashSet::new();
+
         let mut counter = 1;
         for result in reader.records() {
-            let mut raw_ballot = Vec::new();
+            let record = result?;
             counter += 1;
 
-            for value in result?.iter() {
-                raw_ballot.push(value.parse::<usize>().ok())
+            if contest.is_some_and(|(column, name)| record.get(column).map(str::trim) != Some(name)) {
+                continue;
             }
 
-            match Ballot::from_raw_ballot(raw_ballot) {
-                Ok(ballot) => ballot_box.push(ballot, 1),
-                Err(raw_ballot) => reporting::invalid_ballot(counter, &raw_ballot, report),
+            if rules.find_duplicates {
+                // Ballots entered twice would each be given their own ID, so it isn't compared.
+                let row = record.iter().enumerate().filter(|&(column, _)| id != Some(column)).map(|(_, cell)| String::from(cell)).collect();
+
+                if !seen.insert(row) {
+                    ballot_box.duplicate_rows += 1;
+                }
+            }
+
+            // The abstention column is never part of the ballot, so is checked on its own.
+            let abstained = abstained(&record, abstain);
+
+            let parsed = match rules.input {
+                InputFormat::Columns => Ballot::parse_row_keeping(record.iter(), &kinds, rules),
+                InputFormat::Ordered => {
+                    // Rows may run past the header, but the special columns are always in place.
+                    let cells =
+                        record
+                        .iter()
+                        .enumerate()
+                        .filter(|(column, _)| !special.get(*column).copied().unwrap_or(false))
+                        .map(|(_, cell)| cell);
+
+                    Ballot::parse_ordered(cells, &candidate_columns, rules)
+                },
+            };
+
+            match parsed {
+                Ok(ballot) if !filters.iter().all(|(candidate, filter)| filter.matches(*candidate, &ballot)) => ballot_box.filtered += 1,
+                Ok(ballot) => match ballot_box.push(ballot, 1) {
+                    Ok(()) => ballot_box.ballots += 1,
+                    Err(reason) => reporting::invalid_ballot(counter, id.and_then(|column| record.get(column)), &[], reason, formatter, report),
+                },
+                Err((_, InvalidReason::NoPreferences | InvalidReason::OnlyDroppedCandidates)) if abstained => ballot_box.abstentions += 1,
+                Err((raw_ballot, reason)) => reporting::invalid_ballot(counter, id.and_then(|column| record.get(column)), &raw_ballot, reason, formatter, report),
             }
         }
 
         Ok(ballot_box)
     }
 
+    /// Adds all of the votes in another ballot box into this one. Both must have the same
+    /// candidates, in the same order. Each is reset first, and fails to merge if its count is still
+    /// under way, as it has no checkpoint from before counting began. A checkpoint of this ballot
+    /// box is taken again afterwards, so that resetting keeps the merged votes.
+    pub fn merge(&mut self, mut other : BallotBox) -> Result<(), Error> {
+        if self.candidates != other.candidates {
+            return Err(Error::MismatchedCandidates);
+        }
+
+        self.reset();
+        other.reset();
+
+        if self.counting_started() || other.counting_started() {
+            return Err(Error::CountStarted);
+        }
+
+        let checkpointed = self.initial.take().is_some();
+
+        // Candidates are only eliminated if they had no first preferences in either box.
+        for (eliminated, other_eliminated) in self.eliminated.iter_mut().zip(other.eliminated) {
+            *eliminated = *eliminated && other_eliminated;
+        }
+
+        self.total_votes += other.total_votes;
+        self.ballots += other.ballots;
+        self.exhausted += other.exhausted;
+        self.duplicate_rows += other.duplicate_rows;
+        self.abstentions += other.abstentions;
+        self.filtered += other.filtered;
+
+        if self.transfer_levels.len() < other.transfer_levels.len() {
+            self.transfer_levels.resize(other.transfer_levels.len(), 0);
+        }
+        for (level, other_level) in self.transfer_levels.iter_mut().zip(other.transfer_levels) {
+            *level += other_level;
+        }
+
+        for (node, other_node) in self.nodes.iter_mut().zip(other.nodes) {
+            BallotBoxNode::merge_into(node, other_node);
+        }
+
+        if checkpointed {
+            self.checkpoint();
+        }
+
+        Ok(())
+    }
+
     /// Returns a collection of all eliminated candidates.
-    fn eliminated(&self) -> Vec<usize> {
+    pub fn eliminated(&self) -> Vec<usize> {
         let mut eliminated = Vec::new();
 
         for i in 0..self.candidates.len() {
@@ -110,17 +496,210 @@ impl BallotBox {
         eliminated
     }
 
-    /// Returns the number of remaining candidates which have yet to be eliminated.
-    fn remaining(&self) -> usize {
+    /// Returns the current number of top preference votes for each candidate.
+    pub fn totals(&self) -> Vec<u32> {
         self
-        .eliminated
+        .nodes
         .iter()
-        .filter(|b| !*b)
+        .map(|n| match n {
+            None => 0,
+            Some(node) => node.total_beneath,
+        })
+        .collect()
+    }
+
+    /// Returns the current number of top preference votes for the provided candidate, without
+    /// computing the totals of every other candidate.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `candidate` is not the index of one of the ballot box's candidates.
+    pub fn votes_for(&self, candidate : usize) -> u32 {
+        match &self.nodes[candidate] {
+            None => 0,
+            Some(node) => node.total_beneath,
+        }
+    }
+
+    /// Returns the number of valid ballots which were read into the box.
+    pub fn ballots(&self) -> u32 {
+        self.ballots
+    }
+
+    /// Keeps a copy of the ballot box as it is now for `reset` to return to, so that the same
+    /// ballots can be counted more than once. The copy takes as much memory as the ballot box
+    /// itself, so is only kept when asked for. Any earlier copy is replaced.
+    pub fn checkpoint(&mut self) {
+        self.initial = None;
+        self.initial = Some(Box::new(self.clone()));
+    }
+
+    /// Undoes every elimination and promotion since the last `checkpoint`, restoring the votes to
+    /// how they were then so that they can be counted again. The candidates are left as they are.
+    /// Without a checkpoint nothing is changed.
+    pub fn reset(&mut self) {
+        if let Some(initial) = self.initial.take() {
+            let candidates = mem::replace(&mut self.candidates, initial.candidates.clone());
+            *self = (*initial).clone();
+            self.candidates = candidates;
+            self.initial = Some(initial);
+        }
+    }
+
+    /// Returns whether any candidate has been eliminated or promoted yet.
+    fn counting_started(&self) -> bool {
+        self.previous_totals.is_some()
+    }
+
+    /// Returns each candidate's total from the round before this one, or `None` in the first round.
+    pub fn previous_totals(&self) -> Option<&[u32]> {
+        self.previous_totals.as_deref()
+    }
+
+    /// Returns the number of rows read from the file which exactly repeated an earlier row, if
+    /// the rules asked for duplicates to be found.
+    pub fn duplicate_rows(&self) -> u32 {
+        self.duplicate_rows
+    }
+
+    /// Returns the number of valid ballots read from the file which were left out of the count for
+    /// not meeting its filters.
+    pub fn filtered(&self) -> u32 {
+        self.filtered
+    }
+
+    /// Returns the number of rows read from the file which formally abstained, marking the abstention
+    /// column without any preferences.
+    pub fn abstentions(&self) -> u32 {
+        self.abstentions
+    }
+
+    /// Returns the number of votes which have exhausted, having no preferences left for candidates
+    /// still in the count.
+    pub fn exhausted(&self) -> u32 {
+        self.exhausted
+    }
+
+    /// Checks that no votes have been lost or created, so every vote read is either still with a
+    /// candidate or has exhausted.
+    fn votes_conserved(&self) -> bool {
+        let held : u32 = self.totals().iter().sum();
+
+        held == self.total_votes && held + self.exhausted == self.ballots
+    }
+
+    /// Returns the total number of votes still in the count.
+    pub fn total_votes(&self) -> u32 {
+        self.total_votes
+    }
+
+    /// Returns how many votes the provided candidate currently leads the next highest candidate by.
+    pub fn margin(&self, candidate : usize) -> u32 {
+        let totals = self.totals();
+
+        let runner_up =
+            totals
+            .iter()
+            .enumerate()
+            .filter(|(c, _)| c != &candidate)
+            .map(|(_, total)| *total)
+            .max()
+            .unwrap_or(0);
+
+        totals[candidate].saturating_sub(runner_up)
+    }
+
+    /// Returns how many transferred votes have landed on each preference so far, indexed by the
+    /// preference number on the original ballot, so a vote transferred from its first preference to
+    /// its second and then on to its third is counted at 3.
+    pub fn transfer_levels(&self) -> &[u32] {
+        &self.transfer_levels
+    }
+
+    /// Returns the number of candidates with no first preference votes.
+    pub fn without_first_preferences(&self) -> usize {
+        self
+        .nodes
+        .iter()
+        .filter(|node| node.is_none())
         .count()
     }
 
-    /// Adds the provided ballot to the `BallotBox` `quantity` times.
-    fn push(&mut self, ballot : Ballot, quantity : u32) {
+    /// Returns the number of votes which only expressed a first preference.
+    pub fn bullet_votes(&self) -> u32 {
+        self
+        .nodes
+        .iter()
+        .flatten()
+        .map(|node| node.endings)
+        .sum()
+    }
+
+    /// Returns the number of votes which expressed a preference for every candidate.
+    pub fn fully_ranked(&self) -> u32 {
+        self
+        .nodes
+        .iter()
+        .flatten()
+        .map(|node| node.endings_at_depth(self.candidates.len()))
+        .sum()
+    }
+
+    /// Returns how many votes would exhaust if the provided candidates were eliminated, as they
+    /// express no preference for any candidate who would be left. Nothing is changed.
+    pub fn would_exhaust(&self, to_eliminate : &[usize]) -> u32 {
+        let mut gone = self.eliminated.clone();
+        for &candidate in to_eliminate {
+            gone[candidate] = true;
+        }
+
+        self
+        .nodes
+        .iter()
+        .enumerate()
+        .filter(|(candidate, _)| gone[*candidate])
+        .filter_map(|(_, node)| node.as_ref())
+        .map(|node| node.endings_within(&gone))
+        .sum()
+    }
+
+    /// Returns the pairwise preference matrix of the votes, where `[a][b]` is the number of votes
+    /// ranking `a` above `b`, counting an unranked candidate as below every ranked one.
+    pub fn pairwise(&self) -> Vec<Vec<u32>> {
+        let mut matrix = vec![vec![0; self.candidates.len()]; self.candidates.len()];
+
+        for (candidate, node) in self.nodes.iter().enumerate() {
+            if let Some(node) = node {
+                node.add_pairwise(&mut vec![candidate], &mut matrix);
+            }
+        }
+
+        matrix
+    }
+
+    /// Returns whether any vote held by a remaining candidate has a later preference for another
+    /// remaining candidate.
+    fn has_later_preferences(&self) -> bool {
+        let remaining : Vec<bool> = self.eliminated.iter().map(|eliminated| !eliminated).collect();
+
+        self
+        .nodes
+        .iter()
+        .flatten()
+        .any(|node| node.reaches(&remaining))
+    }
+
+    /// Adds the provided ballot to the `BallotBox` `quantity` times, failing without adding anything
+    /// if the ballot can't be counted among the candidates.
+    fn push(&mut self, ballot : Ballot, quantity : u32) -> Result<(), InvalidReason> {
+        self.push_from(ballot, quantity, 0)
+    }
+
+    /// Adds the provided ballot as `push` does, where `start` preferences on the original ballot
+    /// came before its first preference and have since been removed.
+    fn push_from(&mut self, ballot : Ballot, quantity : u32, start : usize) -> Result<(), InvalidReason> {
+        // An empty ballot has no first preference, and a longer one would index past the trie.
+        ballot.check(self.candidates.len())?;
 
         // All candidates are marked as eliminated at the start, so this may need to change as each
         // new ballot is added in.
@@ -159,25 +738,30 @@ impl BallotBox {
         }
 
         // Update the endings count on the last node.
-        current_node.unwrap().endings += quantity;
+        let last_node = current_node.unwrap();
+        last_node.endings += quantity;
+
+        if last_node.endings_by_start.len() <= start {
+            last_node.endings_by_start.resize(start + 1, 0);
+        }
+        last_node.endings_by_start[start] += quantity;
+
+        Ok(())
     }
 
 
     // Gives the current status of the count, and indicates who needs to be eliminated in a runoff
-    // if necessary.
-    pub fn status(&self, threshold : f64, report : bool) -> CountStatus {
-        let totals : Vec<u32> =
-            self
-            .nodes
-            .iter()
-            .map(|n| match n {
-                None => 0,
-                Some(node) => node.total_beneath,
-            })
-            .collect();
+    // if necessary, using `tiebreak` to choose between candidates tied on the fewest votes.
+    pub fn status(&self, threshold : f64, tiebreak : Tiebreak, rng : &mut Rng, formatter : &Formatter, report : bool) -> CountStatus {
+        debug_assert!(self.votes_conserved());
+
+        let totals = self.totals();
 
-        let max = *totals.iter().max().unwrap();
-        let min = *totals.iter().filter(|x| x != &&0).min().unwrap();
+        // Candidates on zero votes are either eliminated or have had all their votes exhausted, so
+        // are never the losers. If everyone is on zero there is no minimum, but the count is
+        // declared a tie below before it is needed.
+        let max = totals.iter().copied().max().unwrap_or(0);
+        let min = totals.iter().copied().filter(|x| x != &0).min().unwrap_or(0);
 
         let winners =
             totals
@@ -203,26 +787,51 @@ impl BallotBox {
                 losers 
             });
 
-        reporting::current_count(totals.iter().enumerate().map(|(a, b)| (a, *b)).collect(), &self.candidates, report);
+        reporting::current_count(totals.iter().enumerate().map(|(a, b)| (a, *b)).collect(), self.previous_totals(), &self.candidates, formatter, report);
 
+        // The fewest whole votes which meet the threshold against the ballots still in the count.
+        let needed = (threshold * f64::from(self.total_votes)).ceil() as u32;
+        reporting::votes_needed(needed, formatter, report);
+
+        // An uncontested election is won by the only candidate, even if nobody voted.
+        let status = if self.candidates.len() == 1 {
+            CountStatus::Winner(0)
+        }
         // All votes have been reduced to 0.
-        let status = if max == 0 {
-            CountStatus::Tie
+        else if max == 0 {
+            let tied : Vec<usize> = (0..self.candidates.len()).filter(|&c| !self.eliminated[c]).collect();
+
+            match tiebreak.elect(&tied, self.candidates.priority()) {
+                Some(winner) => CountStatus::Winner(winner),
+                None => CountStatus::Tie(tied),
+            }
         }
         // A unique winner has been determined.
         else if winners.len() == 1 && f64::try_from(max).unwrap() >= (threshold * f64::try_from(self.total_votes).unwrap()) {
             CountStatus::Winner(winners[0])
         }
-        // All remaining candidates are on equal votes.
-        else if winners.len() == self.remaining() {
+        // Every candidate with votes is on equal votes, and no vote has a later preference for any
+        // remaining candidate, so promoting would only exhaust every vote and can never break the
+        // tie.
+        else if min == max && tiebreak != Tiebreak::Order && !self.has_later_preferences() {
+            CountStatus::Tie(winners)
+        }
+        // Every candidate with votes is on equal votes. Eliminating them all would leave only
+        // candidates on zero votes, who would then be declared tied without having held a vote
+        // between them, so their later preferences are looked at instead. A priority order breaks
+        // this like any other tie, by eliminating the lowest of them below.
+        else if min == max && tiebreak != Tiebreak::Order {
             CountStatus::Promotion(winners)
         }
-        // Distribute the votes of all losers.
+        // Distribute the votes of the losers.
         else {
-            CountStatus::Runoff(losers)
+            CountStatus::Runoff(tiebreak.eliminate(losers, rng, self.candidates.priority()))
         };
 
-        reporting::status(&status, &self.candidates, report);
+        // Lists are built by walking the totals in index order, and nothing should reorder them.
+        debug_assert!(status.listed().windows(2).all(|pair| pair[0] < pair[1]));
+
+        reporting::status(&status, &self.candidates, formatter, report);
 
         status
     }
@@ -237,9 +846,17 @@ impl BallotBox {
         self.runoff_or_promote(to_eliminate, true);
     }
 
+    /// Returns an iterator which resets the ballot box and counts it one round at a time.
+    pub fn rounds(&mut self, threshold : f64) -> Rounds<'_> {
+        Rounds::new(self, CountConfig::new().threshold(threshold))
+    }
+
     fn runoff_or_promote(&mut self, to_promote_or_eliminate : Vec<usize>, runoff : bool) {
-        // Vector of ballots and the quantity to redistribute.
-        let mut adjusted_votes : Vec<(Ballot, u32)> = Vec::new();
+        self.previous_totals = Some(self.totals());
+
+        // Vector of ballots, the quantity to redistribute and how many preferences came before the
+        // candidate they were held by.
+        let mut adjusted_votes : Vec<(Ballot, u32, usize)> = Vec::new();
 
         for candidate in to_promote_or_eliminate {
             // Swap the votes to distribute out.
@@ -258,21 +875,42 @@ impl BallotBox {
             }
         }
 
-        // Determine all previously eliminated candidates (including in this round).
+        // Determine all previously eliminated candidates (including in this round). This has to come
+        // after every candidate in the batch is marked, so that candidates eliminated together
+        // never receive each other's votes.
         let eliminated_candidates : Vec<usize> = self.eliminated();
 
-        for (vote, qty) in adjusted_votes {
-            // Remove any preferences expressed for the candidates which have already been
-            // eliminated, and add the remaining ballot if it is non-empty.
-            if let Some(vote) = Ballot::remove_candidates(vote, &eliminated_candidates) {
-                self.push(vote, qty);
+        for (vote, qty, start) in adjusted_votes {
+            // Skip the leading preferences for candidates which have already been eliminated, and
+            // add the remaining ballot if it is non-empty.
+            let pushed = match Ballot::skip_candidates(vote, &eliminated_candidates) {
+                Some((vote, skipped)) => {
+                    // The vote was held at preference `start + 1` of the original ballot, so lands
+                    // on the preference after that, past any which were skipped.
+                    let start = start + 1 + skipped;
+                    let level = start + 1;
+                    if self.transfer_levels.len() <= level {
+                        self.transfer_levels.resize(level + 1, 0);
+                    }
+                    self.transfer_levels[level] += qty;
+
+                    self.push_from(vote, qty, start).is_ok()
+                },
+                None => false,
+            };
+
+            if !pushed {
+                self.exhausted += qty;
             }
         }
+
+        debug_assert!(eliminated_candidates.iter().all(|&c| self.nodes[c].is_none()));
+        debug_assert!(self.votes_conserved());
     }
 
     /// Helper function for `runoff_or_promote` which handles the calculating of votes that need to
     /// be distributed.
-    fn distribute(to_distribute : &BallotBoxNode, current_ballot : Vec<usize>, adjusted_votes : &mut Vec<(Ballot, u32)>) {
+    fn distribute(to_distribute : &BallotBoxNode, current_ballot : Vec<usize>, adjusted_votes : &mut Vec<(Ballot, u32, usize)>) {
         for (candidate, child) in to_distribute.children.iter().enumerate() {
             if let Some(node) = child {
                 // Clone the current ballot so that new values can be added as passed down.
@@ -284,13 +922,347 @@ impl BallotBox {
             }
         }
 
-        // Add the current ballot to the collection with the corresponding count.
-        // This will intentionally ignore ballots at the top level, which are being distributed
-        // anyway.
-        if to_distribute.endings > 0 {
-            adjusted_votes.push((Ballot::new(current_ballot), to_distribute.endings));
+        // Add the current ballot to the collection with the corresponding count, once for each
+        // number of preferences that came before it. This will intentionally ignore ballots at the
+        // top level, which are being distributed anyway.
+        for (start, &endings) in to_distribute.endings_by_start.iter().enumerate() {
+            if endings > 0 {
+                adjusted_votes.push((Ballot::new(current_ballot.clone()), endings, start));
+            }
+        }
+    }
+}
+
+/// Lists the contests named in the `column` of a file, in the order they first appear.
+pub fn contests(path : &path::Path, encoding : Encoding, column : &str) -> Result<Vec<String>, Error> {
+    let mut reader =
+        csv::ReaderBuilder::new()
+        .has_headers(true)
+        .flexible(true)
+        .from_reader(input::open(path, encoding)?);
+
+    let index = column_index(reader.headers()?, column)?;
+
+    let mut contests : Vec<String> = Vec::new();
+    for result in reader.records() {
+        let record = result?;
+
+        if let Some(name) = record.get(index).map(str::trim) {
+            if !contests.iter().any(|contest| contest == name) {
+                contests.push(String::from(name));
+            }
         }
     }
+
+    Ok(contests)
+}
+
+/// Returns roughly how many bytes each preference on a ballot may take up in a ballot box with this
+/// many candidates, since every node of the tree has a slot for each candidate.
+pub fn node_size(candidates : usize) -> usize {
+    mem::size_of::<BallotBoxNode>() + candidates * mem::size_of::<Option<BallotBoxNode>>()
 }
 
+/// Finds the index of the column with the provided header.
+fn column_index(headers : &csv::StringRecord, column : &str) -> Result<usize, Error> {
+    headers
+    .iter()
+    .position(|header| header == column)
+    .ok_or_else(|| Error::MissingColumn(String::from(column)))
+}
+
+/// Returns whether a row marks the abstention column, if there is one.
+fn abstained(record : &csv::StringRecord, abstain : Option<usize>) -> bool {
+    abstain
+    .and_then(|column| record.get(column))
+    .is_some_and(|cell| !cell.trim().is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use crate::ballot::PreferenceFormat;
+
+    use super::*;
+
+    /// Reads a ballot file holding `contents` without reporting anything. The file is named after
+    /// the test so that tests running at the same time don't share one.
+    fn read(name : &str, contents : &str, rules : Rules, columns : Columns) -> Result<BallotBox, Error> {
+        let path = std::env::temp_dir().join(format!("vote-counter-{}-{}.csv", name, std::process::id()));
+        std::fs::write(&path, contents).unwrap();
+
+        let ballot_box = BallotBox::from_file(&path, rules, columns, &[], &Formatter::default(), false);
+        std::fs::remove_file(&path).unwrap();
+
+        ballot_box
+    }
+
+    #[test]
+    fn ordered_ballots_skip_the_id_column() {
+        let rules = Rules { input : InputFormat::Ordered, ..Rules::default() };
+
+        let ballot_box = read("ordered-id", "id,A,B,C\n10481,1,0\n10482,2\n10483,0,2,1\n", rules, Columns { id : Some("id"), ..Columns::default() }).unwrap();
+
+        assert_eq!(ballot_box.candidates.len(), 3);
+        assert_eq!(ballot_box.ballots(), 3);
+        assert_eq!(ballot_box.totals(), vec![1, 1, 1]);
+    }
+
+    #[test]
+    fn ordered_ballots_skip_the_abstain_column() {
+        let rules = Rules { input : InputFormat::Ordered, ..Rules::default() };
+
+        let ballot_box = read("ordered-abstain", "A,B,C,abstain\n0,1,,\n,,,1\n2,,,\n", rules, Columns { abstain : Some("abstain"), ..Columns::default() }).unwrap();
+
+        assert_eq!(ballot_box.ballots(), 2);
+        assert_eq!(ballot_box.abstentions(), 1);
+        assert_eq!(ballot_box.totals(), vec![1, 0, 1]);
+    }
+
+    #[test]
+    fn from_ballots_rejects_an_empty_ballot() {
+        let candidates = Candidates::new(vec![String::from("A"), String::from("B")]);
+
+        assert!(matches!(BallotBox::from_ballots(candidates, vec![Ballot::new(vec![])]), Err(InvalidReason::NoPreferences)));
+    }
+
+    #[test]
+    fn from_ballots_rejects_a_repeated_candidate() {
+        let candidates = Candidates::new(vec![String::from("A"), String::from("B")]);
+
+        assert!(matches!(BallotBox::from_ballots(candidates, vec![Ballot::new(vec![0, 0])]), Err(InvalidReason::RepeatedPreference)));
+    }
+
+    #[test]
+    fn dropped_candidates_pass_their_first_preferences_on() {
+        let dropped = [String::from("A")];
 
+        let ballot_box = read("dropped", "A,B,C\n1,2,\n1,,2\n,1,\n", Rules::default(), Columns { dropped : &dropped, ..Columns::default() }).unwrap();
+
+        assert_eq!(ballot_box.candidates.len(), 2);
+        assert_eq!(ballot_box.totals(), vec![2, 1]);
+    }
+
+    #[test]
+    fn dropping_a_missing_column_fails() {
+        let dropped = [String::from("D")];
+
+        let result = read("dropped-missing", "A,B,C\n1,2,\n", Rules::default(), Columns { dropped : &dropped, ..Columns::default() });
+
+        assert!(matches!(result, Err(Error::MissingColumn(column)) if column == "D"));
+    }
+
+    #[test]
+    fn transfer_levels_count_from_the_original_ballot() {
+        let candidates = Candidates::new(["A", "B", "C", "D"].map(String::from).to_vec());
+        let ballots = vec![Ballot::new(vec![0, 1, 2, 3]), Ballot::new(vec![1]), Ballot::new(vec![2]), Ballot::new(vec![3])];
+        let mut ballot_box = BallotBox::from_ballots(candidates, ballots).unwrap();
+
+        ballot_box.runoff(vec![2]);
+        ballot_box.runoff(vec![0]);
+        assert_eq!(ballot_box.transfer_levels(), &[0, 0, 1]);
+
+        // Having been held at its second preference, the vote skips C and lands on its fourth.
+        ballot_box.runoff(vec![1]);
+        assert_eq!(ballot_box.transfer_levels(), &[0, 0, 1, 0, 1]);
+        assert_eq!(ballot_box.totals(), vec![0, 0, 0, 2]);
+    }
+
+    /// Builds a ballot box of the `ballots`, with candidates named A, B, C and so on.
+    fn ballot_box(candidates : usize, ballots : &[&[usize]]) -> BallotBox {
+        let candidates = Candidates::new((0..candidates).map(|c| char::from(b'A' + c as u8).to_string()).collect());
+        let ballots = ballots.iter().map(|ballot| Ballot::new(ballot.to_vec())).collect();
+
+        BallotBox::from_ballots(candidates, ballots).unwrap()
+    }
+
+    /// Gives the status of the count, breaking no ties and reporting nothing.
+    fn status(ballot_box : &BallotBox, threshold : f64) -> CountStatus {
+        ballot_box.status(threshold, Tiebreak::All, &mut Rng::from_seed(0), &Formatter::default(), false)
+    }
+
+    #[test]
+    fn candidates_eliminated_together_never_receive_each_others_votes() {
+        let mut ballot_box = ballot_box(4, &[&[0, 1, 2], &[1, 0], &[2], &[2], &[3], &[3]]);
+        assert_eq!(status(&ballot_box, 0.5), CountStatus::Runoff(vec![0, 1]));
+
+        ballot_box.runoff(vec![0, 1]);
+
+        assert_eq!(ballot_box.totals(), vec![0, 0, 3, 2]);
+        assert_eq!(ballot_box.exhausted(), 1);
+    }
+
+    #[test]
+    fn leader_short_of_the_threshold_is_not_the_winner() {
+        let ballot_box = ballot_box(2, &[&[0], &[0], &[1]]);
+
+        assert_eq!(status(&ballot_box, 0.5), CountStatus::Winner(0));
+        assert_eq!(status(&ballot_box, 0.75), CountStatus::Runoff(vec![1]));
+    }
+
+    #[test]
+    fn candidates_on_equal_votes_with_nowhere_to_go_are_tied() {
+        let ballot_box = ballot_box(3, &[&[0], &[1]]);
+
+        // C never had a vote, so isn't part of the tie.
+        assert_eq!(status(&ballot_box, 0.5), CountStatus::Tie(vec![0, 1]));
+    }
+
+    #[test]
+    fn candidates_on_equal_votes_with_later_preferences_are_promoted() {
+        let ballot_box = ballot_box(3, &[&[0, 1], &[1, 2], &[2, 0]]);
+
+        assert_eq!(status(&ballot_box, 0.5), CountStatus::Promotion(vec![0, 1, 2]));
+    }
+
+    #[test]
+    fn promotion_never_eliminates_every_candidate() {
+        let mut ballot_box = ballot_box(3, &[&[0, 1], &[1, 0], &[2]]);
+        assert_eq!(status(&ballot_box, 0.5), CountStatus::Promotion(vec![0, 1, 2]));
+
+        // The votes swap between A and B, and C's exhausts, leaving A and B tied.
+        ballot_box.promote(vec![0, 1, 2]);
+
+        assert_eq!(ballot_box.totals(), vec![1, 1, 0]);
+        assert_eq!(ballot_box.exhausted(), 1);
+        assert_eq!(status(&ballot_box, 0.5), CountStatus::Tie(vec![0, 1]));
+    }
+
+    #[test]
+    fn only_candidate_wins_without_any_votes() {
+        let ballot_box = ballot_box(1, &[]);
+
+        assert_eq!(status(&ballot_box, 0.5), CountStatus::Winner(0));
+    }
+
+    #[test]
+    fn merging_adds_the_ballots_of_both_boxes() {
+        let mut first = ballot_box(2, &[&[0, 1], &[1]]);
+        first.merge(ballot_box(2, &[&[0]])).unwrap();
+
+        assert_eq!(first.totals(), vec![2, 1]);
+        assert_eq!(first.ballots(), 3);
+    }
+
+    #[test]
+    fn merging_different_candidates_fails() {
+        let mut first = ballot_box(2, &[&[0]]);
+
+        assert!(matches!(first.merge(ballot_box(3, &[&[0]])), Err(Error::MismatchedCandidates)));
+    }
+
+    #[test]
+    fn crlf_line_endings_and_padded_cells_are_read() {
+        let ballot_box = read("crlf", "A,B\r\n 1 ,2 \r\n2,1\r\n", Rules::default(), Columns::default()).unwrap();
+
+        assert_eq!(ballot_box.candidates.key(1).map(String::as_str), Some("B"));
+        assert_eq!(ballot_box.totals(), vec![1, 1]);
+        assert_eq!(ballot_box.ballots(), 2);
+    }
+
+    #[test]
+    fn preferences_past_the_maximum_are_not_counted() {
+        let rules = Rules { max_preferences : NonZeroUsize::new(1), ..Rules::default() };
+        let mut ballot_box = read("max-preferences", "A,B,C\n1,2,\n,1,\n,,1\n,,1\n", rules, Columns::default()).unwrap();
+
+        // A's second preference for B was cut, so the vote exhausts rather than transferring.
+        ballot_box.runoff(vec![0]);
+
+        assert_eq!(ballot_box.totals(), vec![0, 1, 2]);
+        assert_eq!(ballot_box.exhausted(), 1);
+    }
+
+    #[test]
+    fn id_column_is_not_counted_as_a_candidate() {
+        let rules = Rules { format : PreferenceFormat::Letter, ..Rules::default() };
+
+        // C is past the two candidates, and 102 is a blank ballot, so only 103 is counted.
+        let ballot_box = read("id-letters", "id,A,B\n101,A,C\n102,,\n103,B,A\n", rules, Columns { id : Some("id"), ..Columns::default() }).unwrap();
+
+        assert_eq!(ballot_box.ballots(), 1);
+        assert_eq!(ballot_box.totals(), vec![0, 1]);
+    }
+
+    #[test]
+    fn contest_column_is_not_counted_as_a_candidate() {
+        let rules = Rules { format : PreferenceFormat::Letter, ..Rules::default() };
+        let contest = Contest { column : "contest", name : "Mayor" };
+
+        let ballot_box = read("contest-letters", "contest,A,B\nMayor,A,C\nMayor,B,A\n", rules, Columns { contest : Some(contest), ..Columns::default() }).unwrap();
+
+        assert_eq!(ballot_box.ballots(), 1);
+        assert_eq!(ballot_box.totals(), vec![0, 1]);
+    }
+
+    #[test]
+    fn reset_returns_to_the_checkpoint() {
+        let mut ballot_box = ballot_box(3, &[&[0], &[0], &[1, 0], &[2]]);
+        ballot_box.checkpoint();
+
+        ballot_box.runoff(vec![1, 2]);
+        ballot_box.reset();
+        assert_eq!(ballot_box.totals(), vec![2, 1, 1]);
+
+        // The checkpoint is kept, so the count can be reset again.
+        ballot_box.runoff(vec![1, 2]);
+        ballot_box.reset();
+        assert_eq!(ballot_box.totals(), vec![2, 1, 1]);
+    }
+
+    #[test]
+    fn merging_into_a_count_under_way_fails() {
+        let other = ballot_box(2, &[&[1]]);
+        let mut ballot_box = ballot_box(2, &[&[0], &[1, 0], &[0]]);
+        ballot_box.runoff(vec![1]);
+
+        assert!(matches!(ballot_box.merge(other), Err(Error::CountStarted)));
+    }
+
+    #[test]
+    fn merged_votes_survive_a_reset() {
+        let other = ballot_box(2, &[&[1]]);
+        let mut ballot_box = ballot_box(2, &[&[0], &[1, 0], &[0]]);
+        ballot_box.checkpoint();
+        ballot_box.runoff(vec![1]);
+
+        ballot_box.merge(other).unwrap();
+        ballot_box.runoff(vec![1]);
+        ballot_box.reset();
+
+        assert_eq!(ballot_box.totals(), vec![2, 2]);
+    }
+
+    #[test]
+    fn letter_ballots_are_read_from_a_file() {
+        let rules = Rules { format : PreferenceFormat::Letter, ..Rules::default() };
+
+        // D is past the three candidates, so the last ballot is invalid.
+        let ballot_box = read("letters", "A,B,C\na,B,C\nB,A,\nA,D,\n", rules, Columns::default()).unwrap();
+
+        assert_eq!(ballot_box.ballots(), 2);
+        assert_eq!(ballot_box.totals(), vec![1, 1, 0]);
+    }
+
+    #[test]
+    fn votes_exhausted_by_a_promotion_are_still_accounted_for() {
+        let mut ballot_box = ballot_box(2, &[&[0], &[1, 0], &[1]]);
+
+        ballot_box.promote(vec![0, 1]);
+
+        assert_eq!(ballot_box.totals(), vec![1, 0]);
+        assert_eq!(ballot_box.total_votes(), 1);
+        assert_eq!(ballot_box.exhausted(), 2);
+        assert_eq!(ballot_box.total_votes() + ballot_box.exhausted(), ballot_box.ballots());
+    }
+
+    #[test]
+    fn unmet_threshold_is_reached_once_one_candidate_is_left() {
+        let mut ballot_box = ballot_box(2, &[&[0], &[0], &[0], &[0], &[0], &[0], &[1], &[1], &[1], &[1]]);
+
+        // A's 60% falls short of 90%, so B is eliminated, leaving A with every vote still counted.
+        let statuses : Vec<CountStatus> = ballot_box.rounds(0.9).map(|round| round.status).collect();
+
+        assert_eq!(statuses, vec![CountStatus::Runoff(vec![1]), CountStatus::Winner(0)]);
+    }
+}
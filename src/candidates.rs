@@ -1,20 +1,217 @@
-/// Collection of candidates, in the same order as the `csv`.
-#[derive(Debug, Clone)]
-pub struct Candidates(Vec<String>);
+use std::collections::HashMap;
+use std::path;
+
+use crate::ballot::PreferenceFormat;
+
+/// Collection of candidates, in the same order as the `csv`. Each candidate is identified by their
+/// column header, but may be displayed under a different name and in a different order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Candidates {
+    keys : Vec<String>,
+    names : Vec<String>,
+    order : Vec<usize>,
+    priority : Vec<usize>,
+    parties : Vec<Option<String>>,
+}
 
 impl Candidates {
-    /// Creates a new instance of `Candidates` from a `Vec<String>`.
+    /// Creates a new instance of `Candidates` from a `Vec<String>` of column headers.
     pub fn new(candidates : Vec<String>) -> Self {
-        Candidates(candidates)
+        Candidates {
+            names : candidates.clone(),
+            order : (0..candidates.len()).collect(),
+            priority : (0..candidates.len()).collect(),
+            parties : vec![None; candidates.len()],
+            keys : candidates,
+        }
     }
 
-    /// Gets a candidate's name based on their index.
+    /// Gets a candidate's display name based on their index.
     pub fn get(&self, candidate : usize) -> Option<&String> {
-        self.0.get(candidate)
+        self.names.get(candidate)
+    }
+
+    /// Gets a candidate's column header based on their index.
+    pub fn key(&self, candidate : usize) -> Option<&String> {
+        self.keys.get(candidate)
+    }
+
+    /// Finds a candidate's index based on their column header.
+    pub fn index(&self, key : &str) -> Option<usize> {
+        self.keys.iter().position(|k| k == key)
+    }
+
+    /// Sets the display names of candidates from a map of column header to name. Candidates
+    /// missing from the map are displayed by their column header.
+    pub fn set_names(&mut self, names : &HashMap<String, String>) {
+        self.names =
+            self.keys
+            .iter()
+            .map(|key| names.get(key).unwrap_or(key).clone())
+            .collect();
+    }
+
+    /// Sets the party of each candidate from a map of column header to party. Candidates missing
+    /// from the map, or mapped to a blank party, have no party.
+    pub fn set_parties(&mut self, parties : &HashMap<String, String>) {
+        self.parties =
+            self.keys
+            .iter()
+            .map(|key| parties.get(key).filter(|party| !party.trim().is_empty()).cloned())
+            .collect();
+    }
+
+    /// Gets a candidate's party based on their index, if they have one.
+    pub fn party(&self, candidate : usize) -> Option<&String> {
+        self.parties.get(candidate).and_then(Option::as_ref)
+    }
+
+    /// Returns whether any candidate has a party.
+    pub fn has_parties(&self) -> bool {
+        self.parties.iter().any(Option::is_some)
+    }
+
+    /// Sets the order candidates are displayed in from a list of column headers. Candidates missing
+    /// from the list are displayed after those in it, in the order of the file.
+    pub fn set_order(&mut self, keys : &[String]) {
+        let mut order : Vec<usize> = Vec::with_capacity(self.len());
+
+        for candidate in keys.iter().filter_map(|key| self.index(key)) {
+            if !order.contains(&candidate) {
+                order.push(candidate);
+            }
+        }
+
+        for candidate in 0..self.len() {
+            if !order.contains(&candidate) {
+                order.push(candidate);
+            }
+        }
+
+        self.order = order;
+    }
+
+    /// Gets the position a candidate is displayed at based on their index.
+    pub fn position(&self, candidate : usize) -> usize {
+        self.order.iter().position(|&c| c == candidate).unwrap_or(candidate)
+    }
+
+    /// Sets the priority order used to break ties from a list of column headers, from highest to
+    /// lowest priority. Every candidate must be in the list, and the header of the first one
+    /// missing is returned otherwise.
+    pub fn set_priority(&mut self, keys : &[String]) -> Result<(), String> {
+        let mut priority : Vec<usize> = Vec::with_capacity(self.len());
+
+        for candidate in keys.iter().filter_map(|key| self.index(key)) {
+            if !priority.contains(&candidate) {
+                priority.push(candidate);
+            }
+        }
+
+        if let Some(missing) = (0..self.len()).find(|c| !priority.contains(c)) {
+            return Err(self.keys[missing].clone());
+        }
+
+        self.priority = priority;
+        Ok(())
+    }
+
+    /// Returns every candidate from highest to lowest priority for breaking ties, which is the
+    /// order of the file unless set otherwise.
+    pub fn priority(&self) -> &[usize] {
+        &self.priority
     }
 
     /// Returns the number of candidates.
     pub fn len(&self) -> usize {
-        self.0.len()
+        self.keys.len()
+    }
+
+    /// Returns whether there are no candidates.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Returns whether the column headers look like a ballot rather than candidates, with every
+    /// header either blank or a preference number, which suggests the file has no header row.
+    pub fn look_like_ballot(&self) -> bool {
+        self.keys.iter().any(|key| !key.trim().is_empty())
+            && self.keys.iter().all(|key| key.trim().is_empty() || PreferenceFormat::Ordinal.parse(key).is_some())
+    }
+}
+
+/// Reads a file listing one value on each row, such as a candidate's column header.
+pub fn read_list(path : &path::Path) -> Result<Vec<String>, csv::Error> {
+    let mut reader =
+        csv::ReaderBuilder::new()
+        .has_headers(false)
+        .from_path(path)?;
+
+    let mut list = Vec::new();
+    for result in reader.records() {
+        if let Some(value) = result?.get(0) {
+            list.push(String::from(value));
+        }
     }
+
+    Ok(list)
+}
+
+/// Reads a file mapping each candidate's column header to some other value, where each row is
+/// `header,value`.
+pub fn read_mapping(path : &path::Path) -> Result<HashMap<String, String>, csv::Error> {
+    let mut reader =
+        csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_path(path)?;
+
+    let mut map = HashMap::new();
+    for result in reader.records() {
+        let record = result?;
+
+        if let (Some(key), Some(value)) = (record.get(0), record.get(1)) {
+            map.insert(String::from(key), String::from(value));
+        }
+    }
+
+    Ok(map)
+}
+
+/// Parties and display names of candidates, as given in a candidates file.
+#[derive(Debug, Clone, Default)]
+pub struct CandidatesFile {
+    /// Party of each candidate given one, by column header.
+    pub parties : HashMap<String, String>,
+    /// Display name of each candidate given one, by column header.
+    pub names : HashMap<String, String>,
+}
+
+/// Reads a candidates file, where each row is `header,party,name` and the party and name may be
+/// left blank or out.
+pub fn read_candidates(path : &path::Path) -> Result<CandidatesFile, csv::Error> {
+    let mut reader =
+        csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_path(path)?;
+
+    let mut file = CandidatesFile::default();
+    for result in reader.records() {
+        let record = result?;
+
+        let Some(key) = record.get(0) else {
+            continue;
+        };
+
+        if let Some(party) = record.get(1).filter(|party| !party.trim().is_empty()) {
+            file.parties.insert(String::from(key), String::from(party));
+        }
+
+        if let Some(name) = record.get(2).filter(|name| !name.trim().is_empty()) {
+            file.names.insert(String::from(key), String::from(name));
+        }
+    }
+
+    Ok(file)
 }
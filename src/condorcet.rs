@@ -0,0 +1,26 @@
+/// Where a candidate stands in head to head contests against every other candidate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Standing {
+    /// The candidate beats every other candidate head to head.
+    Winner,
+    /// The candidate loses to every other candidate head to head.
+    Loser,
+    /// The candidate neither beats nor loses to every other candidate.
+    Neither,
+}
+
+/// Finds where a candidate stands from a pairwise preference matrix, where `[a][b]` is the number
+/// of votes ranking `a` above `b`.
+pub fn standing(matrix : &[Vec<u32>], candidate : usize) -> Standing {
+    let others = (0..matrix.len()).filter(|&other| other != candidate);
+
+    if others.clone().all(|other| matrix[candidate][other] > matrix[other][candidate]) {
+        Standing::Winner
+    }
+    else if others.clone().all(|other| matrix[candidate][other] < matrix[other][candidate]) {
+        Standing::Loser
+    }
+    else {
+        Standing::Neither
+    }
+}
@@ -0,0 +1,257 @@
+use crate::ballot_box::{
+    BallotBox,
+    CountStatus,
+    CountStatus::*
+};
+use std::str::FromStr;
+
+use crate::formatting::Formatter;
+use crate::random::Rng;
+use crate::tiebreak::Tiebreak;
+
+/// Options for a count. The defaults give a plain instant runoff, needing a majority to win, with
+/// all candidates tied on the fewest votes eliminated together.
+#[derive(Debug, Clone, Copy)]
+pub struct CountConfig {
+    /// Share of the votes still in the count needed to win (from 0.0 to 1.0).
+    pub threshold : f64,
+    /// How to choose between candidates tied on the fewest votes.
+    pub tiebreak : Tiebreak,
+    /// Seed for any random decisions.
+    pub seed : u64,
+}
+
+impl Default for CountConfig {
+    fn default() -> Self {
+        CountConfig {
+            threshold : 0.5,
+            tiebreak : Tiebreak::All,
+            seed : 0,
+        }
+    }
+}
+
+impl CountConfig {
+    /// Creates a config with the default options.
+    pub fn new() -> Self {
+        CountConfig::default()
+    }
+
+    /// Sets the share of the votes needed to win.
+    pub fn threshold(mut self, threshold : f64) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Sets how to choose between candidates tied on the fewest votes.
+    pub fn tiebreak(mut self, tiebreak : Tiebreak) -> Self {
+        self.tiebreak = tiebreak;
+        self
+    }
+
+    /// Sets the seed for any random decisions.
+    pub fn seed(mut self, seed : u64) -> Self {
+        self.seed = seed;
+        self
+    }
+}
+
+/// Range of thresholds to count the same ballots at, written `start:step:end`.
+#[derive(Debug, Clone, Copy)]
+pub struct ThresholdSweep {
+    /// First threshold counted at.
+    pub start : f64,
+    /// Amount the threshold goes up by each time.
+    pub step : f64,
+    /// Last threshold counted at, if a whole number of steps reaches it.
+    pub end : f64,
+}
+
+impl ThresholdSweep {
+    /// Returns each threshold in the range, from `start` up to and including `end`.
+    pub fn thresholds(&self) -> Vec<f64> {
+        // Multiplying out each step, rather than adding them up, stops rounding errors building up.
+        let steps = ((self.end - self.start) / self.step + 1e-9).floor() as usize;
+
+        (0..=steps).map(|i| self.start + i as f64 * self.step).collect()
+    }
+}
+
+impl FromStr for ThresholdSweep {
+    type Err = String;
+
+    fn from_str(range : &str) -> Result<Self, Self::Err> {
+        let parts : Vec<&str> = range.split(':').collect();
+
+        let [start, step, end] = parts[..] else {
+            return Err(String::from("expected start:step:end"));
+        };
+
+        let parse = |part : &str| part.trim().parse::<f64>().map_err(|error| error.to_string());
+
+        let sweep = ThresholdSweep {
+            start : parse(start)?,
+            step : parse(step)?,
+            end : parse(end)?,
+        };
+
+        if sweep.step <= 0.0 || sweep.start > sweep.end || sweep.start < 0.0 || sweep.end > 1.0 {
+            return Err(String::from("expected a positive step, with 0.0 <= start <= end <= 1.0"));
+        }
+
+        Ok(sweep)
+    }
+}
+
+/// How the result of the count is treated once the election's other rules are applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    /// The result of the count stands.
+    Stands,
+    /// "None of the above" won, so the election must be rerun.
+    Inconclusive,
+    /// The winner had fewer votes than the quorum, which is this many votes.
+    NoQuorum(u32),
+}
+
+impl Verdict {
+    /// Decides the verdict on the `result` of a count, where `nota` is the "none of the above"
+    /// candidate, `quorum` the fewest votes the winner must finish with, and `totals` each
+    /// candidate's final total.
+    pub fn of(result : &CountStatus, nota : Option<usize>, quorum : Option<u32>, totals : &[u32]) -> Verdict {
+        match *result {
+            Winner(winner) if Some(winner) == nota => Verdict::Inconclusive,
+            Winner(winner) if quorum.is_some_and(|quorum| totals[winner] < quorum) => Verdict::NoQuorum(quorum.unwrap()),
+            _ => Verdict::Stands,
+        }
+    }
+
+    /// Returns the exit code the program should finish with for this verdict. An election which
+    /// must be rerun exits with code 75, so that scripts can tell it apart from a result.
+    pub fn exit_code(&self) -> exitcode::ExitCode {
+        match self {
+            Verdict::Stands => exitcode::OK,
+            Verdict::Inconclusive | Verdict::NoQuorum(_) => exitcode::TEMPFAIL,
+        }
+    }
+}
+
+/// One round of a count, yielded by `Rounds`.
+#[derive(Debug, Clone)]
+pub struct Round {
+    /// Round number, starting from 1.
+    pub round : usize,
+    /// Top preference votes for each candidate at the start of the round.
+    pub totals : Vec<u32>,
+    /// Candidates eliminated before the round.
+    pub eliminated : Vec<usize>,
+    /// Decision made from the round.
+    pub status : CountStatus,
+}
+
+/// State of the count after a round, passed to the callback given to `run_election`.
+pub type RoundInfo = Round;
+
+/// Iterator over the rounds of a count, carrying out each round's decision as it is yielded and
+/// ending after the round which finds a winner or a tie.
+pub struct Rounds<'a> {
+    ballot_box : &'a mut BallotBox,
+    config : CountConfig,
+    rng : Rng,
+    formatter : Formatter,
+    round : usize,
+    finished : bool,
+}
+
+impl<'a> Rounds<'a> {
+    /// Starts a count of the ballot box under the config. Like `run_election`, the ballot box is
+    /// reset to its checkpoint first, if it has one.
+    pub fn new(ballot_box : &'a mut BallotBox, config : CountConfig) -> Self {
+        ballot_box.reset();
+
+        Rounds {
+            ballot_box,
+            rng : Rng::from_seed(config.seed),
+            config,
+            formatter : Formatter::default(),
+            round : 0,
+            finished : false,
+        }
+    }
+}
+
+impl Iterator for Rounds<'_> {
+    type Item = Round;
+
+    fn next(&mut self) -> Option<Round> {
+        if self.finished {
+            return None;
+        }
+
+        self.round += 1;
+
+        let totals = self.ballot_box.totals();
+        let eliminated = self.ballot_box.eliminated();
+        let status = self.ballot_box.status(self.config.threshold, self.config.tiebreak, &mut self.rng, &self.formatter, false);
+
+        match status.clone() {
+            Winner(_) | Tie(_) => self.finished = true,
+            Runoff(to_eliminate) => self.ballot_box.runoff(to_eliminate),
+            Promotion(to_promote) => self.ballot_box.promote(to_promote),
+        }
+
+        Some(Round {
+            round : self.round,
+            totals,
+            eliminated,
+            status,
+        })
+    }
+}
+
+/// Runs a full count on an already filled ballot box without printing anything, returning the
+/// winner, or `None` if the election was a tie. If provided, `on_round` is called after each round
+/// is decided. The ballot box is reset first, so one with a checkpoint can be counted again under a
+/// different configuration without reading the ballots again.
+pub fn run_election(ballot_box : &mut BallotBox, config : &CountConfig, mut on_round : Option<&mut dyn FnMut(&RoundInfo)>) -> Option<usize> {
+    let mut result = None;
+
+    for round in Rounds::new(ballot_box, *config) {
+        if let Some(on_round) = on_round.as_mut() {
+            on_round(&round);
+        }
+
+        if let Winner(winner) = round.status {
+            result = Some(winner);
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn none_of_the_above_winning_exits_with_code_75() {
+        let verdict = Verdict::of(&Winner(2), Some(2), None, &[1, 2, 5]);
+
+        assert_eq!(verdict, Verdict::Inconclusive);
+        assert_eq!(verdict.exit_code(), 75);
+    }
+
+    #[test]
+    fn missing_the_quorum_exits_with_code_75() {
+        let verdict = Verdict::of(&Winner(0), None, Some(6), &[5, 2]);
+
+        assert_eq!(verdict, Verdict::NoQuorum(6));
+        assert_eq!(verdict.exit_code(), 75);
+    }
+
+    #[test]
+    fn a_result_which_stands_exits_successfully() {
+        assert_eq!(Verdict::of(&Winner(0), Some(1), Some(5), &[5, 2]).exit_code(), 0);
+        assert_eq!(Verdict::of(&Tie(vec![0, 1]), Some(1), None, &[2, 2]).exit_code(), 0);
+    }
+}
@@ -0,0 +1,32 @@
+use clap::ValueEnum;
+
+/// Text encoding of a ballot file.
+#[derive(Debug, Clone, Copy, Default, ValueEnum)]
+pub enum Encoding {
+    /// UTF-8.
+    #[default]
+    Utf8,
+    /// UTF-16, little endian.
+    Utf16le,
+    /// UTF-16, big endian.
+    Utf16be,
+}
+
+impl Encoding {
+    /// Detects the encoding from a byte order mark at the start of the file, returning it along
+    /// with the rest of the file after the mark.
+    pub(crate) fn detect(bytes : &[u8]) -> Option<(Encoding, &[u8])> {
+        if let Some(rest) = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]) {
+            Some((Encoding::Utf8, rest))
+        }
+        else if let Some(rest) = bytes.strip_prefix(&[0xFF, 0xFE]) {
+            Some((Encoding::Utf16le, rest))
+        }
+        else if let Some(rest) = bytes.strip_prefix(&[0xFE, 0xFF]) {
+            Some((Encoding::Utf16be, rest))
+        }
+        else {
+            None
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use std::io;
+
+/// Errors which stop a count from completing.
+#[derive(Debug)]
+pub enum Error {
+    /// The ballot file could not be read or parsed.
+    Csv(csv::Error),
+    /// An output file could not be written.
+    Io(io::Error),
+    /// Counting the same ballots twice gave different results.
+    VerificationFailed,
+    /// Ballot files being counted together have different candidates.
+    MismatchedCandidates,
+    /// Fewer valid ballots were read than the provided minimum.
+    TooFewBallots(u32),
+    /// The count ran for more than the maximum number of rounds.
+    TooManyRounds(usize),
+    /// A candidate was missing from the priority order used to break ties.
+    MissingFromTiebreakOrder(String),
+    /// A column named in the options isn't in the ballot file.
+    MissingColumn(String),
+    /// The first row of the ballot file looks like a ballot rather than a header of candidates.
+    MissingHeader,
+    /// The ballot file has more candidates, the first value, than the maximum, the second.
+    TooManyCandidates(usize, usize),
+    /// The first option has no effect when combined with the second.
+    IncompatibleOptions(&'static str, &'static str),
+    /// Ballots were added to a count already under way, which had no checkpoint to reset it to.
+    CountStarted,
+    /// The option was given a value which isn't a fraction from 0.0 to 1.0.
+    NotAFraction(&'static str, f64),
+}
+
+impl Error {
+    /// Returns the exit code the program should finish with for this error.
+    pub fn exit_code(&self) -> exitcode::ExitCode {
+        match self {
+            Error::Csv(_) => exitcode::DATAERR,
+            Error::Io(_) => exitcode::IOERR,
+            Error::VerificationFailed => exitcode::SOFTWARE,
+            Error::MismatchedCandidates => exitcode::DATAERR,
+            Error::TooFewBallots(_) => exitcode::DATAERR,
+            Error::TooManyRounds(_) => exitcode::SOFTWARE,
+            Error::MissingFromTiebreakOrder(_) => exitcode::DATAERR,
+            Error::MissingColumn(_) => exitcode::DATAERR,
+            Error::MissingHeader => exitcode::DATAERR,
+            Error::TooManyCandidates(_, _) => exitcode::DATAERR,
+            Error::IncompatibleOptions(_, _) => exitcode::USAGE,
+            Error::NotAFraction(_, _) => exitcode::USAGE,
+            Error::CountStarted => exitcode::SOFTWARE,
+        }
+    }
+}
+
+impl From<csv::Error> for Error {
+    fn from(error : csv::Error) -> Self {
+        Error::Csv(error)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error : io::Error) -> Self {
+        Error::Io(error)
+    }
+}
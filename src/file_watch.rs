@@ -0,0 +1,47 @@
+use std::fs;
+use std::path;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// How often the file is checked for modifications.
+const POLL_INTERVAL : Duration = Duration::from_millis(250);
+
+/// How long the file must go unmodified after a change before it is considered saved, so that a
+/// burst of writes from a single save only triggers one recount.
+const DEBOUNCE : Duration = Duration::from_millis(500);
+
+/// Returns the last modification time of the file, if it can be read.
+fn modified(path : &path::Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Blocks until the file at `path` has been modified and then left alone for the debounce period.
+pub fn wait_for_change(path : &path::Path) {
+    let initial = modified(path);
+
+    // Wait for the first sign of a change.
+    let mut last = loop {
+        thread::sleep(POLL_INTERVAL);
+
+        let current = modified(path);
+        if current != initial {
+            break current;
+        }
+    };
+
+    // Wait for the writes to settle.
+    loop {
+        thread::sleep(DEBOUNCE);
+
+        let current = modified(path);
+        if current == last {
+            break;
+        }
+        last = current;
+    }
+}
+
+/// Clears the terminal and moves the cursor back to the top left.
+pub fn clear_screen() {
+    print!("\x1B[2J\x1B[H");
+}
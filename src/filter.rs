@@ -0,0 +1,106 @@
+use std::str::FromStr;
+
+use crate::ballot::Ballot;
+
+/// How a candidate's rank on a ballot is compared against a number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Equal,
+    GreaterOrEqual,
+    Greater,
+}
+
+impl Comparison {
+    /// Compares `rank` against `other` with this comparison.
+    fn holds(&self, rank : usize, other : usize) -> bool {
+        match self {
+            Comparison::Less => rank < other,
+            Comparison::LessOrEqual => rank <= other,
+            Comparison::Equal => rank == other,
+            Comparison::GreaterOrEqual => rank >= other,
+            Comparison::Greater => rank > other,
+        }
+    }
+}
+
+/// Predicate a ballot must meet to be counted, naming candidates by their column header. Written
+/// on the command line as either:
+///
+/// - `ranks:<candidate><comparison><rank>`, where the comparison is one of `<`, `<=`, `=`, `>=`
+///   or `>`, matching ballots which rank the candidate and whose rank for them (starting from 1)
+///   compares as given. `ranks:Alice<=3` matches ballots with Alice in their top three.
+/// - `first:<candidate>`, matching ballots which give the candidate their first preference.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Filter {
+    Ranks {
+        candidate : String,
+        comparison : Comparison,
+        rank : usize,
+    },
+    First(String),
+}
+
+impl Filter {
+    /// Returns the column header of the candidate the filter is about.
+    pub fn candidate(&self) -> &str {
+        match self {
+            Filter::Ranks { candidate, .. } | Filter::First(candidate) => candidate,
+        }
+    }
+
+    /// Returns whether the ballot meets the filter, where `candidate` is the index of the
+    /// candidate it names. Ranks are the positions of preferences still on the ballot once it has
+    /// been read, so are counted after any dropped candidates are removed.
+    pub fn matches(&self, candidate : usize, ballot : &Ballot) -> bool {
+        match self {
+            Filter::Ranks { comparison, rank, .. } => {
+                ballot
+                .iter()
+                .position(|&c| c == candidate)
+                .is_some_and(|position| comparison.holds(position + 1, *rank))
+            },
+            Filter::First(_) => ballot.first_pref() == candidate,
+        }
+    }
+}
+
+impl FromStr for Filter {
+    type Err = String;
+
+    fn from_str(filter : &str) -> Result<Self, Self::Err> {
+        if let Some(candidate) = filter.strip_prefix("first:") {
+            return Ok(Filter::First(String::from(candidate)));
+        }
+
+        let Some(predicate) = filter.strip_prefix("ranks:") else {
+            return Err(String::from("expected ranks:<candidate><comparison><rank> or first:<candidate>"));
+        };
+
+        // Two character comparisons are tried first, so `<=` isn't read as `<` followed by `=`.
+        let comparisons = [
+            ("<=", Comparison::LessOrEqual),
+            (">=", Comparison::GreaterOrEqual),
+            ("<", Comparison::Less),
+            (">", Comparison::Greater),
+            ("=", Comparison::Equal),
+        ];
+
+        let Some((candidate, comparison, rank)) =
+            comparisons
+            .iter()
+            .find_map(|(symbol, comparison)| predicate.rsplit_once(symbol).map(|(candidate, rank)| (candidate, *comparison, rank)))
+        else {
+            return Err(String::from("expected a comparison of <, <=, =, >= or >"));
+        };
+
+        let rank = rank.trim().parse::<usize>().map_err(|error| error.to_string())?;
+
+        Ok(Filter::Ranks {
+            candidate : String::from(candidate),
+            comparison,
+            rank,
+        })
+    }
+}
@@ -0,0 +1,192 @@
+use clap::ValueEnum;
+
+use crate::candidates::Candidates;
+use crate::messages::{Lang, Message};
+
+/// Locale used to choose the thousands separator when displaying numbers.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum Locale {
+    /// No separator (1234567).
+    Plain,
+    /// Comma separated (1,234,567).
+    En,
+    /// Period separated (1.234.567).
+    De,
+    /// Space separated (1 234 567).
+    Fr,
+}
+
+impl Locale {
+    /// Returns the thousands separator for the locale, if any.
+    fn separator(&self) -> Option<char> {
+        match self {
+            Locale::Plain => None,
+            Locale::En => Some(','),
+            Locale::De => Some('.'),
+            Locale::Fr => Some(' '),
+        }
+    }
+
+    /// Returns the decimal separator for the locale.
+    fn decimal(&self) -> char {
+        match self {
+            Locale::Plain | Locale::En => '.',
+            Locale::De | Locale::Fr => ',',
+        }
+    }
+}
+
+/// Layout of the report of the count.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum ReportFormat {
+    /// Coloured text for the terminal.
+    #[default]
+    Text,
+    /// A Markdown document, with a heading for each round and a table of the standings.
+    Markdown,
+    /// A line of JSON for each round and then the result, the same as `--jsonl`.
+    Json,
+}
+
+/// Controls how values are presented in the output.
+#[derive(Clone, Copy, Debug)]
+pub struct Formatter {
+    /// Locale used to format numbers.
+    pub locale : Locale,
+    /// Language of the messages.
+    pub lang : Lang,
+    /// Number of decimal places shown in percentages.
+    pub percent_decimals : usize,
+    /// Show candidates by their index rather than by name.
+    pub by_index : bool,
+    /// List candidates from most to fewest votes, rather than in the order of the file.
+    pub sort_count : bool,
+    /// Show the count as an aligned table with borders.
+    pub table : bool,
+    /// Follow each candidate's votes in the count with a bar in proportion to the leader's.
+    pub bars : bool,
+    /// Follow each candidate's votes in the count with how many they gained or lost since the round
+    /// before.
+    pub deltas : bool,
+    /// Layout of the report.
+    pub report_format : ReportFormat,
+    /// Longest a candidate's name is shown in the report before it is cut short with an ellipsis.
+    pub name_width : Option<usize>,
+}
+
+impl Default for Formatter {
+    /// Plain English output, matching the defaults of the command line.
+    fn default() -> Self {
+        Formatter {
+            locale : Locale::Plain,
+            lang : Lang::En,
+            percent_decimals : 1,
+            by_index : false,
+            sort_count : false,
+            table : false,
+            bars : false,
+            deltas : false,
+            report_format : ReportFormat::Text,
+            name_width : None,
+        }
+    }
+}
+
+impl Formatter {
+    /// Formats a candidate, either by name or by their index in the file. Names longer than
+    /// `name_width` are cut short with an ellipsis.
+    pub fn candidate(&self, candidate : usize, candidates : &Candidates) -> String {
+        let formatted = self.candidate_in_full(candidate, candidates);
+
+        match self.name_width {
+            Some(width) if formatted.chars().count() > width => {
+                let mut truncated : String = formatted.chars().take(width.saturating_sub(1)).collect();
+                truncated.push('…');
+                truncated
+            },
+            _ => formatted,
+        }
+    }
+
+    /// Formats a candidate like `candidate`, but never cuts their name short. Used for output read
+    /// by other programs.
+    pub fn candidate_in_full(&self, candidate : usize, candidates : &Candidates) -> String {
+        if self.by_index {
+            candidate.to_string()
+        }
+        else {
+            candidates.get(candidate).unwrap().clone()
+        }
+    }
+
+    /// Looks up the text of a message in the configured language.
+    pub fn text(&self, message : Message) -> &'static str {
+        message.text(self.lang)
+    }
+
+    /// Formats a vote count, grouping the digits according to the locale.
+    pub fn number(&self, value : u32) -> String {
+        let digits = value.to_string();
+
+        match self.locale.separator() {
+            None => digits,
+            Some(separator) => {
+                // The leading group holds whatever digits don't divide evenly into threes.
+                let leading = match digits.len() % 3 {
+                    0 => 3,
+                    n => n,
+                };
+                let (first, rest) = digits.split_at(leading.min(digits.len()));
+
+                let mut formatted = String::from(first);
+                for group in rest.as_bytes().chunks(3) {
+                    formatted.push(separator);
+                    formatted.push_str(std::str::from_utf8(group).unwrap());
+                }
+
+                formatted
+            }
+        }
+    }
+
+    /// Formats the change from `before` to `after` with its sign, such as `+37` or `-120`.
+    pub fn change(&self, before : u32, after : u32) -> String {
+        if after >= before {
+            format!("+{}", self.number(after - before))
+        }
+        else {
+            format!("-{}", self.number(before - after))
+        }
+    }
+
+    /// Formats `part` as a percentage of `whole`. All percentages shown to the user should go
+    /// through here so they are displayed to the same precision.
+    pub fn percent(&self, part : u32, whole : u32) -> String {
+        let percentage = match whole {
+            0 => 0.0,
+            _ => f64::from(part) * 100.0 / f64::from(whole),
+        };
+
+        format!("{}%", self.decimal(percentage))
+    }
+
+    /// Formats a decimal value to the same precision as percentages.
+    pub fn decimal(&self, value : f64) -> String {
+        let formatted = format!("{:.*}", self.percent_decimals, value);
+
+        formatted.replace('.', &self.locale.decimal().to_string())
+    }
+}
+
+/// Width of the bar shown for the candidate with the most votes.
+pub const BAR_WIDTH : usize = 40;
+
+/// Draws a bar for `votes` in proportion to `max`, which gets the full width.
+pub fn bar(votes : u32, max : u32) -> String {
+    let width = match max {
+        0 => 0,
+        _ => u64::from(votes) * BAR_WIDTH as u64 / u64::from(max),
+    };
+
+    "█".repeat(width as usize)
+}
@@ -0,0 +1,90 @@
+use std::fs;
+use std::io::{self, Read};
+use std::path;
+
+use crate::encoding::Encoding;
+
+/// Opens a ballot file as a stream of UTF-8, transcoding it if it is in some other encoding.
+pub fn open(path : &path::Path, encoding : Encoding) -> io::Result<Box<dyn Read>> {
+    utf8_reader(fs::File::open(path)?, encoding)
+}
+
+/// Wraps a reader so that it yields UTF-8 with any byte order mark stripped. A byte order mark at
+/// the start decides the encoding, and `encoding` is only used for input without one.
+pub fn utf8_reader(mut reader : impl Read + 'static, encoding : Encoding) -> io::Result<Box<dyn Read>> {
+    // A byte order mark is at most three bytes long.
+    let mut start = Vec::with_capacity(3);
+    reader.by_ref().take(3).read_to_end(&mut start)?;
+
+    let (encoding, after_mark) = Encoding::detect(&start).unwrap_or((encoding, &start));
+    let rest = io::Cursor::new(after_mark.to_vec()).chain(reader);
+
+    let from_bytes = match encoding {
+        Encoding::Utf8 => return Ok(Box::new(rest)),
+        Encoding::Utf16le => u16::from_le_bytes,
+        Encoding::Utf16be => u16::from_be_bytes,
+    };
+
+    Ok(Box::new(io::Cursor::new(decode_utf16(rest, from_bytes)?)))
+}
+
+/// Reads the rest of a UTF-16 stream into UTF-8, with `from_bytes` deciding the byte order.
+fn decode_utf16(mut reader : impl Read, from_bytes : fn([u8; 2]) -> u16) -> io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+
+    if bytes.len() % 2 != 0 {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "UTF-16 file has an odd number of bytes"));
+    }
+
+    let units = bytes.chunks_exact(2).map(|pair| from_bytes([pair[0], pair[1]]));
+
+    let decoded =
+        char::decode_utf16(units)
+        .collect::<Result<String, _>>()
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+
+    Ok(decoded.into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reads the header of a CSV file made of `bytes`, decoded as `utf8_reader` would.
+    fn headers(bytes : &'static [u8], encoding : Encoding) -> Vec<String> {
+        let mut reader = csv::Reader::from_reader(utf8_reader(bytes, encoding).unwrap());
+
+        reader.headers().unwrap().iter().map(String::from).collect()
+    }
+
+    #[test]
+    fn utf16le_is_detected_from_its_byte_order_mark() {
+        let bytes = b"\xFF\xFEA\x00,\x00B\x00\n\x001\x00,\x002\x00\n\x00";
+
+        assert_eq!(headers(bytes, Encoding::Utf8), ["A", "B"]);
+    }
+
+    #[test]
+    fn utf16be_is_detected_from_its_byte_order_mark() {
+        let bytes = b"\xFE\xFF\x00A\x00,\x00B\x00\n\x001\x00,\x002\x00\n";
+
+        assert_eq!(headers(bytes, Encoding::Utf8), ["A", "B"]);
+    }
+
+    #[test]
+    fn input_without_a_byte_order_mark_uses_the_given_encoding() {
+        assert_eq!(headers(b"A\x00,\x00\xE9\x00\n\x00", Encoding::Utf16le), ["A", "é"]);
+        assert_eq!(headers(b"A,\xC3\xA9\n", Encoding::Utf8), ["A", "é"]);
+    }
+
+    #[test]
+    fn odd_length_utf16_is_rejected() {
+        assert!(utf8_reader(&b"\xFF\xFEA\x00,"[..], Encoding::Utf8).is_err());
+    }
+
+    #[test]
+    fn utf8_byte_order_mark_is_stripped() {
+        assert_eq!(headers(b"\xEF\xBB\xBFA,B\n1,2\n", Encoding::Utf8), ["A", "B"]);
+    }
+}
@@ -0,0 +1,22 @@
+//! An opinionated single transferrable vote counter.
+
+pub mod ballot_box;
+pub mod reporting;
+pub mod candidates;
+pub mod ballot;
+pub mod formatting;
+pub mod messages;
+pub mod audit;
+pub mod error;
+pub mod random;
+pub mod tiebreak;
+pub mod allocation;
+pub mod stats;
+pub mod election;
+pub mod encoding;
+pub mod json;
+pub mod input;
+pub mod condorcet;
+pub mod manifest;
+pub mod filter;
+pub mod sha256;
@@ -1,19 +1,37 @@
-mod ballot_box;
-mod reporting;
-mod candidates;
-mod ballot;
+mod watch;
+mod file_watch;
 
-use ballot_box::BallotBox;
-use ballot_box::CountStatus::*;
+use vote_counter::ballot_box::{self, BallotBox, Columns, Contest};
+use vote_counter::ballot::{EqualRanks, InputFormat, PreferenceFormat, Rules};
+use vote_counter::ballot_box::CountStatus;
+use vote_counter::ballot_box::CountStatus::*;
+use vote_counter::audit::AuditLog;
+use vote_counter::encoding::Encoding;
+use vote_counter::election::{self, CountConfig, ThresholdSweep, Verdict};
+use vote_counter::error::Error;
+use vote_counter::filter::Filter;
+use vote_counter::formatting::{Formatter, Locale, ReportFormat};
+use vote_counter::messages::{Lang, Message};
+use vote_counter::random::{self, Rng};
+use vote_counter::tiebreak::Tiebreak;
+use vote_counter::stats::Statistics;
+use vote_counter::candidates::CandidatesFile;
+use vote_counter::{allocation, candidates, condorcet, manifest, reporting, sha256};
 
+use std::env;
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::iter;
+use std::num::NonZeroUsize;
 use std::path;
 use std::process;
+use std::time::Instant;
 
 use clap::Parser;
 
-/// Adjusts threshold to be within permitted range, warning the user.
-fn adjust_threshold(threshold : f64) -> f64 {
-    reporting::threshold_squash(threshold);
+/// Adjusts threshold to be within permitted range, warning the user unless `quiet` is set.
+fn adjust_threshold(threshold : f64, formatter : &Formatter, quiet : bool) -> f64 {
+    reporting::threshold_squash(threshold, formatter, !quiet);
     if threshold < 0.0 {
         0.0
     }
@@ -32,46 +50,765 @@ struct Args {
     #[clap()]
     path : path::PathBuf,
 
-    /// Threshold to win (from 0.0 to 1.0).
-    #[clap(long, short, default_value = "0.5")]
-    threshold : f64,
+    /// Threshold to win (from 0.0 to 1.0). Defaults to 0.5.
+    #[clap(long, short)]
+    threshold : Option<f64>,
 
     /// Generate report of counting.
     #[clap(long, takes_value = false)]
     report : bool,
+
+    /// Print only the final round's totals along with the result, without the rounds before it.
+    #[clap(long, takes_value = false)]
+    summary_only : bool,
+
+    /// Don't print warnings about settings which were adjusted, and print the result as a single
+    /// tab separated line.
+    #[clap(long, takes_value = false)]
+    quiet : bool,
+
+    /// Locale used to group digits in printed vote counts.
+    #[clap(long, value_enum, default_value = "plain")]
+    locale : Locale,
+
+    /// Language of the messages shown.
+    #[clap(long, value_enum, default_value = "en")]
+    lang : Lang,
+
+    /// Number of decimal places shown in percentages.
+    #[clap(long, default_value = "1")]
+    percent_decimals : usize,
+
+    /// Redraw the standings in the terminal as each round is counted (falls back to the report
+    /// when not writing to a terminal).
+    #[clap(long, takes_value = false)]
+    watch : bool,
+
+    /// Keep running, and recount whenever the ballot file changes.
+    #[clap(long, takes_value = false)]
+    watch_file : bool,
+
+    /// Append a timestamped record of every counting decision to this file.
+    #[clap(long)]
+    audit_log : Option<path::PathBuf>,
+
+    /// Write a JSON manifest of the count to this file, holding the SHA-256 hash of each ballot
+    /// file, the command line arguments and seed used, and the result.
+    #[clap(long)]
+    manifest : Option<path::PathBuf>,
+
+    /// How to choose between candidates tied on the fewest votes.
+    #[clap(long, value_enum, default_value = "all")]
+    tiebreak : Tiebreak,
+
+    /// Priority order for `--tiebreak order`, with one column header on each row from highest to
+    /// lowest priority. Every candidate must be listed. Defaults to the order of the ballot file.
+    #[clap(long)]
+    tiebreak_order : Option<path::PathBuf>,
+
+    /// Seed for random decisions, so that a count can be reproduced exactly. One is generated (and
+    /// printed) if not provided.
+    #[clap(long)]
+    seed : Option<u64>,
+
+    /// Refuse ballot files with more than this many candidates, which could use a great deal of
+    /// memory.
+    #[clap(long, default_value = "1000")]
+    max_candidates : usize,
+
+    /// Only count each ballot's first this many preferences.
+    #[clap(long)]
+    max_preferences : Option<NonZeroUsize>,
+
+    /// Treat ballots expressing fewer than this many preferences as invalid.
+    #[clap(long, default_value = "1")]
+    min_preferences : usize,
+
+    /// Fail if fewer than this many valid ballots are read.
+    #[clap(long, default_value = "0")]
+    min_ballots : u32,
+
+    /// How to read ballots which give the same preference to more than one candidate.
+    #[clap(long, value_enum, default_value = "discard")]
+    equal_ranks : EqualRanks,
+
+    /// Fail, rather than warn, if the first row of the ballot file looks like a ballot instead of a
+    /// header of candidates.
+    #[clap(long, takes_value = false)]
+    require_header : bool,
+
+    /// Warn in the report about rows of the ballot file which exactly repeat an earlier row, as a
+    /// sign of ballots entered twice. The duplicates are still counted.
+    #[clap(long, takes_value = false)]
+    find_duplicates : bool,
+
+    /// How the ballots are laid out in the file. With `ordered`, each row lists candidate indices
+    /// (starting from 0) in order of preference, under the usual header row of candidates.
+    #[clap(long, value_enum, default_value = "columns")]
+    input_format : InputFormat,
+
+    /// Encoding of ballot files which don't start with a byte order mark.
+    #[clap(long, value_enum, default_value = "utf8")]
+    encoding : Encoding,
+
+    /// How the preferences in the file are written.
+    #[clap(long, value_enum, default_value = "number")]
+    preference_format : PreferenceFormat,
+
+    /// Allocate seats between parties by first preference votes instead of counting preferences,
+    /// using a CSV where each row is `candidate,party`.
+    #[clap(long)]
+    party_map : Option<path::PathBuf>,
+
+    /// CSV where each row is `candidate,party,name`, giving each candidate's party and display name
+    /// in one place. Either may be left blank. Parties are only used when allocating seats with
+    /// `--party-map`, and otherwise the count is unaffected.
+    #[clap(long)]
+    candidates_file : Option<path::PathBuf>,
+
+    /// Number of seats to allocate between parties.
+    #[clap(long, default_value = "1")]
+    seats : usize,
+
+    /// Method used to allocate seats between parties.
+    #[clap(long, value_enum, default_value = "dhondt")]
+    allocation : allocation::Method,
+
+    /// Display statistics of the ballots before counting.
+    #[clap(long, takes_value = false)]
+    stats : bool,
+
+    /// Display candidates under the names in this CSV, where each row is `header,name`.
+    #[clap(long)]
+    names : Option<path::PathBuf>,
+
+    /// List candidates in the report in the order of this file, with one column header on each row,
+    /// rather than in the order of the ballot file.
+    #[clap(long)]
+    display_order : Option<path::PathBuf>,
+
+    /// Cut candidates' names short after this many characters in the report, with an ellipsis,
+    /// and list their full names once before the count.
+    #[clap(long)]
+    name_width : Option<usize>,
+
+    /// Show candidates by their index in the file (starting from 0) rather than by name.
+    #[clap(long, takes_value = false)]
+    by_index : bool,
+
+    /// Count the ballots twice in independent passes before the real count, and fail if the two
+    /// disagree at any round.
+    #[clap(long, takes_value = false)]
+    verify : bool,
+
+    /// List candidates in the report from most to fewest votes.
+    #[clap(long, takes_value = false)]
+    sort_count : bool,
+
+    /// Count the ballots at each threshold from `start` to `end` in steps of `step`, written
+    /// `start:step:end`, and show the winner at each rather than counting once.
+    #[clap(long)]
+    threshold_sweep : Option<ThresholdSweep>,
+
+    /// After the count, note whether the winner is also the Condorcet winner or loser.
+    #[clap(long, takes_value = false)]
+    condorcet_check : bool,
+
+    /// Follow each candidate's votes in the report with how many they gained or lost since the
+    /// round before.
+    #[clap(long, takes_value = false)]
+    deltas : bool,
+
+    /// Follow each candidate's votes in the report with a bar in proportion to the leader's.
+    #[clap(long, takes_value = false)]
+    bars : bool,
+
+    /// Layout of the report. `json` prints the same lines as `--jsonl`.
+    #[clap(long, value_enum, default_value = "text")]
+    report_format : ReportFormat,
+
+    /// Show the count in the report as an aligned table with borders.
+    #[clap(long, takes_value = false)]
+    table : bool,
+
+    /// Additional CSVs of ballots with the same candidates, counted together with the main file.
+    #[clap(long)]
+    merge : Vec<path::PathBuf>,
+
+    /// Header of a column marking a formal abstention. It isn't a candidate, and a ballot marking it
+    /// without any preferences is counted as an abstention rather than as invalid.
+    #[clap(long)]
+    abstain_column : Option<String>,
+
+    /// Header of a column naming the contest of each row, for files which stack several contests
+    /// over the same candidate columns. Each contest is counted separately, in the order they first
+    /// appear.
+    #[clap(long)]
+    contest_column : Option<String>,
+
+    /// Remove the candidate with this column header from the file before counting, as though they
+    /// never stood. Their preferences are removed from every ballot rather than transferred, so
+    /// the next preference on each ballot counts from the first round. Fails if no column has this
+    /// header.
+    #[clap(long, visible_alias = "ignore")]
+    drop_column : Vec<String>,
+
+    /// Only count ballots meeting this predicate, either `ranks:<candidate><comparison><rank>` with
+    /// a comparison of `<`, `<=`, `=`, `>=` or `>`, or `first:<candidate>`. Can be given more than
+    /// once, in which case ballots must meet every predicate.
+    #[clap(long)]
+    filter : Vec<Filter>,
+
+    /// Header, or index from 0, of a column holding each ballot's ID, such as a serial number. It
+    /// isn't a candidate, and invalid ballots are reported by their ID.
+    #[clap(long)]
+    id_column : Option<String>,
+
+    /// Column header of a "none of the above" candidate. It is counted like any other, but if it
+    /// wins the election is declared inconclusive and the program exits with code 75.
+    #[clap(long)]
+    nota : Option<String>,
+
+    /// Read the ballots and report on them, but stop before counting.
+    #[clap(long, takes_value = false)]
+    dry_run : bool,
+
+    /// Abandon the count if it runs for more than this many rounds. Defaults to twice the number of
+    /// candidates, plus ten.
+    #[clap(long)]
+    max_rounds : Option<usize>,
+
+    /// Fraction (from 0.0 to 1.0) of all valid ballots, including exhausted ones, which the winner's
+    /// final total must reach. Otherwise there is no quorum and the program exits with code 75.
+    #[clap(long)]
+    quorum : Option<f64>,
+
+    /// Print each round, and then the result, as a line of JSON as the count goes.
+    #[clap(long, takes_value = false)]
+    jsonl : bool,
+
+    /// Write each round's totals and decision as JSON to its own numbered file in this directory,
+    /// which is created if it doesn't exist.
+    #[clap(long)]
+    rounds_dir : Option<path::PathBuf>,
+
+    /// Print one line each round naming the leader and their share of the votes.
+    #[clap(long, takes_value = false)]
+    trace_leader : bool,
+
+    /// Print which round the count has reached and how many candidates remain (to stderr), unless
+    /// `--jsonl` or `--quiet` is set.
+    #[clap(long, takes_value = false)]
+    progress_rounds : bool,
+
+    /// Print how long reading and counting the ballots took (to stderr).
+    #[clap(long, takes_value = false)]
+    timings : bool,
+
+    /// Warn if the winner's final margin over the runner up is below this percentage of the votes.
+    #[clap(long)]
+    recount_margin : Option<f64>,
+}
+
+/// Reads the ballots from the main file, and merges in those from any additional files. With
+/// `--manifest`, the SHA-256 hash of each file as it was read is returned alongside, in order.
+fn read_ballots(args : &Args, rules : Rules, contest : Option<Contest>, formatter : &Formatter, report : bool) -> Result<(BallotBox, Vec<String>), Error> {
+    let columns = Columns {
+        dropped : &args.drop_column,
+        contest,
+        abstain : args.abstain_column.as_deref(),
+        id : args.id_column.as_deref(),
+    };
+
+    let mut hashes = Vec::new();
+
+    let (mut ballot_box, hash) = read_ballot_file(args, &args.path, rules, columns, formatter, report)?;
+    hashes.extend(hash);
+
+    for path in &args.merge {
+        let (other, hash) = read_ballot_file(args, path, rules, columns, formatter, report)?;
+        ballot_box.merge(other)?;
+        hashes.extend(hash);
+    }
+
+    // An emptied or truncated file would otherwise be counted as though nothing was wrong.
+    if ballot_box.ballots() < args.min_ballots {
+        return Err(Error::TooFewBallots(args.min_ballots));
+    }
+
+    if let Some(tiebreak_order) = &args.tiebreak_order {
+        let priority = candidates::read_list(tiebreak_order)?;
+        ballot_box.candidates.set_priority(&priority).map_err(Error::MissingFromTiebreakOrder)?;
+    }
+
+    Ok((ballot_box, hashes))
+}
+
+/// Reads a single ballot file. With `--manifest`, the file is read into memory first so that the
+/// SHA-256 hash returned is of exactly the bytes which were counted, even if the file changes on
+/// disk during the count.
+fn read_ballot_file(args : &Args, path : &path::Path, rules : Rules, columns : Columns, formatter : &Formatter, report : bool) -> Result<(BallotBox, Option<String>), Error> {
+    if args.manifest.is_none() {
+        return Ok((BallotBox::from_file(path, rules, columns, &args.filter, formatter, report)?, None));
+    }
+
+    let contents = fs::read(path)?;
+    let hash = sha256::hex_digest(&contents);
+
+    let ballot_box = BallotBox::from_reader(io::Cursor::new(contents), rules, columns, &args.filter, formatter, report)?;
+
+    Ok((ballot_box, Some(hash)))
+}
+
+/// Returns the number of rounds after which the count is abandoned. Each round either eliminates a
+/// candidate or promotes past a preference, so a correct count needs at most twice as many rounds
+/// as there are candidates.
+fn max_rounds(args : &Args, ballot_box : &BallotBox) -> usize {
+    args.max_rounds.unwrap_or(2 * ballot_box.candidates.len() + 10)
+}
+
+/// Reads and counts the ballots without any output, returning the decision made in each round.
+fn silent_count(args : &Args, rules : Rules, contest : Option<Contest>, config : &CountConfig, formatter : &Formatter) -> Result<Vec<CountStatus>, Error> {
+    let (mut ballot_box, _) = read_ballots(args, rules, contest, formatter, false)?;
+    let mut rng = Rng::from_seed(config.seed);
+
+    let max_rounds = max_rounds(args, &ballot_box);
+
+    let mut decisions = Vec::new();
+    loop {
+        if decisions.len() == max_rounds {
+            return Err(Error::TooManyRounds(max_rounds));
+        }
+
+        let status = ballot_box.status(config.threshold, config.tiebreak, &mut rng, formatter, false);
+        decisions.push(status.clone());
+
+        match status {
+            Winner(_) | Tie(_) => break,
+            Runoff(to_eliminate) => ballot_box.runoff(to_eliminate),
+            Promotion(to_promote) => ballot_box.promote(to_promote),
+        }
+    }
+
+    Ok(decisions)
+}
+
+/// Counts each contest in the ballot file separately if it holds several, and otherwise counts the
+/// whole file, returning the exit code to finish with.
+fn count_contests(args : &Args, formatter : &Formatter) -> Result<exitcode::ExitCode, Error> {
+    check_options(args)?;
+
+    // Warnings would break the single line or JSON lines the scripting modes promise.
+    let threshold = adjust_threshold(args.threshold.unwrap_or(0.5), formatter, args.quiet || args.jsonl);
+
+    // Every contest shares the one seed, so it's only announced once.
+    let seed = match args.seed {
+        Some(seed) => seed,
+        None => {
+            let seed = random::system_seed();
+            // Only worth mentioning if the seed will actually be used.
+            if args.tiebreak == Tiebreak::Random {
+                reporting::seed(seed, formatter, args.quiet || args.jsonl);
+            }
+            seed
+        }
+    };
+
+    let column = match &args.contest_column {
+        Some(column) => column,
+        None => return count(args, None, threshold, seed, formatter),
+    };
+
+    let mut code = exitcode::OK;
+
+    for name in ballot_box::contests(&args.path, args.encoding, column)? {
+        reporting::contest(&name, args.jsonl, args.quiet, formatter);
+
+        let contest_code = count(args, Some(Contest { column, name : &name }), threshold, seed, formatter)?;
+
+        // The first contest that didn't elect anyone decides the exit code.
+        if code == exitcode::OK {
+            code = contest_code;
+        }
+    }
+
+    Ok(code)
+}
+
+/// Rejects options which would be silently ignored because of another option given with them, or
+/// given a value they can't use.
+fn check_options(args : &Args) -> Result<(), Error> {
+    if let Some(quorum) = args.quorum {
+        if !(0.0..=1.0).contains(&quorum) {
+            return Err(Error::NotAFraction("--quorum", quorum));
+        }
+    }
+
+    if args.threshold.is_some() {
+        if args.party_map.is_some() {
+            return Err(Error::IncompatibleOptions("--threshold", "--party-map"));
+        }
+
+        if args.threshold_sweep.is_some() {
+            return Err(Error::IncompatibleOptions("--threshold", "--threshold-sweep"));
+        }
+    }
+
+    // Seats are allocated by first preferences alone, so there are no ties to break.
+    if args.party_map.is_some() {
+        if args.tiebreak != Tiebreak::All {
+            return Err(Error::IncompatibleOptions("--tiebreak", "--party-map"));
+        }
+
+        if args.seed.is_some() {
+            return Err(Error::IncompatibleOptions("--seed", "--party-map"));
+        }
+    }
+
+    if args.manifest.is_some() {
+        if args.contest_column.is_some() {
+            return Err(Error::IncompatibleOptions("--manifest", "--contest-column"));
+        }
+
+        if args.party_map.is_some() {
+            return Err(Error::IncompatibleOptions("--manifest", "--party-map"));
+        }
+
+        if args.threshold_sweep.is_some() {
+            return Err(Error::IncompatibleOptions("--manifest", "--threshold-sweep"));
+        }
+    }
+
+    Ok(())
 }
 
-/// Primary entry point to vote counting algorithms.
-fn count(mut args : Args) -> Result<(), csv::Error> {
+/// Primary entry point to vote counting algorithms, returning the exit code to finish with. If a
+/// `contest` is provided, only its rows of the file are counted. The `threshold` and `seed` are
+/// those already settled by `count_contests`.
+fn count(args : &Args, contest : Option<Contest>, threshold : f64, seed : u64, formatter : &Formatter) -> Result<exitcode::ExitCode, Error> {
+
+    // Watching only makes sense in a terminal, so otherwise fall back to the plain report.
+    let watch = args.watch && io::stdout().is_terminal();
+    let report = args.report || (args.watch && !watch);
+
+    let config =
+        CountConfig::new()
+        .threshold(threshold)
+        .tiebreak(args.tiebreak)
+        .seed(seed);
+
+    let mut rng = Rng::from_seed(config.seed);
+
+    let rules = Rules {
+        max_preferences : args.max_preferences,
+        min_preferences : args.min_preferences,
+        format : args.preference_format,
+        encoding : args.encoding,
+        equal_ranks : args.equal_ranks,
+        input : args.input_format,
+        find_duplicates : args.find_duplicates,
+        max_candidates : Some(args.max_candidates),
+    };
+
+    if args.verify && silent_count(args, rules, contest, &config, formatter)? != silent_count(args, rules, contest, &config, formatter)? {
+        return Err(Error::VerificationFailed);
+    }
+
+    // A dry run always reports what it finds in the file, since that is the point of it.
+    let report_reading = report || args.dry_run;
+
+    let parsing = Instant::now();
+    let (mut ballot_box, hashes) = read_ballots(args, rules, contest, formatter, report_reading)?;
+
+    if args.timings {
+        reporting::timing(Message::Parsing, parsing.elapsed(), formatter);
+    }
+
+    // A file without a header would silently lose its first ballot to the candidate names.
+    if ballot_box.candidates.look_like_ballot() {
+        if args.require_header {
+            return Err(Error::MissingHeader);
+        }
 
-    args.threshold = adjust_threshold(args.threshold);
+        reporting::header_looks_like_ballot(formatter, report_reading);
+    }
 
-    let mut ballot_box = BallotBox::from_file(&args.path, args.report)?;
+    // A win for none of the above means nobody was elected, and the election has to be rerun. A
+    // misspelt header would otherwise let it win as an ordinary candidate.
+    let nota = match &args.nota {
+        Some(nota) => Some(ballot_box.candidates.index(nota).ok_or_else(|| Error::MissingColumn(nota.clone()))?),
+        None => None,
+    };
+
+    reporting::duplicate_rows(ballot_box.duplicate_rows(), formatter, report_reading);
+    reporting::ballots_read(ballot_box.ballots(), ballot_box.total_votes(), formatter, report_reading);
+    reporting::abstentions(ballot_box.abstentions(), formatter, report_reading && args.abstain_column.is_some());
+    reporting::filtered(ballot_box.filtered(), formatter, report_reading && !args.filter.is_empty());
+    reporting::ignored(&args.drop_column, formatter, report_reading);
+
+    let mut candidates_file = match &args.candidates_file {
+        Some(path) => candidates::read_candidates(path)?,
+        None => CandidatesFile::default(),
+    };
+
+    // Names given by `--names` and parties given by `--party-map` take precedence over those in the
+    // candidates file.
+    if let Some(names) = &args.names {
+        candidates_file.names.extend(candidates::read_mapping(names)?);
+    }
+
+    if let Some(party_map) = &args.party_map {
+        candidates_file.parties.extend(candidates::read_mapping(party_map)?);
+    }
+
+    ballot_box.candidates.set_names(&candidates_file.names);
+    ballot_box.candidates.set_parties(&candidates_file.parties);
+
+    if let Some(display_order) = &args.display_order {
+        ballot_box.candidates.set_order(&candidates::read_list(display_order)?);
+    }
+
+    reporting::name_legend(&ballot_box.candidates, formatter, report_reading);
+
+    if args.stats || args.dry_run {
+        reporting::statistics(&Statistics::from_ballot_box(&ballot_box), formatter);
+    }
+
+    if args.dry_run {
+        reporting::dry_run(formatter);
+        return Ok(exitcode::OK);
+    }
+
+    if args.party_map.is_some() {
+        let parties = allocation::party_totals(&ballot_box.totals(), &ballot_box.candidates);
+
+        let allocated = allocation::allocate(&parties, args.seats, args.allocation);
+
+        let gallagher = allocation::gallagher(&parties, &allocated);
+
+        if args.jsonl {
+            reporting::jsonl_allocation(&parties, &allocated, gallagher);
+        }
+        else {
+            reporting::allocation(&parties, &allocated, formatter);
+            reporting::gallagher(gallagher, formatter);
+        }
+
+        return Ok(exitcode::OK);
+    }
+
+    if let Some(sweep) = &args.threshold_sweep {
+        // Each threshold is counted from the same starting point.
+        ballot_box.checkpoint();
+
+        let results : Vec<(f64, Option<usize>)> =
+            sweep
+            .thresholds()
+            .into_iter()
+            .map(|threshold| (threshold, election::run_election(&mut ballot_box, &config.threshold(threshold), None)))
+            .collect();
+
+        reporting::threshold_sweep(&results, &ballot_box.candidates, formatter);
+
+        return Ok(exitcode::OK);
+    }
+
+    // Only opened once it's certain there is a count to record.
+    let mut audit_log = match &args.audit_log {
+        Some(path) => Some(AuditLog::open(path)?),
+        None => None,
+    };
+
+    if let Some(audit_log) = audit_log.as_mut() {
+        audit_log.start(&args.path, config.threshold, config.tiebreak, config.seed, &ballot_box.candidates)?;
+    }
     
-    let winner = loop {
-        match ballot_box.status(args.threshold, args.report) {
-            Winner(winner) => break Some(winner),
-            Tie => break None,
-            Runoff(to_eliminated) => ballot_box.runoff(to_eliminated),
+    let first_round = ballot_box.totals();
+
+    // Counting changes the ballot box, so the head to head contests are taken from the start.
+    let pairwise = args.condorcet_check.then(|| ballot_box.pairwise());
+    reporting::threshold_out_of_reach(&first_round, ballot_box.total_votes(), config.threshold, formatter, report);
+
+    if let Some(rounds_dir) = &args.rounds_dir {
+        fs::create_dir_all(rounds_dir)?;
+    }
+
+    let counting = Instant::now();
+    let max_rounds = max_rounds(args, &ballot_box);
+
+    // Each eliminated candidate, along with the round they were eliminated in.
+    let mut eliminations = Vec::new();
+
+    let mut round = 0;
+    let result = loop {
+        round += 1;
+
+        // The state the count was abandoned in is always shown, in whichever form the output takes.
+        if round > max_rounds {
+            if args.jsonl {
+                reporting::jsonl_stopped(&ballot_box.totals(), &eliminations, &ballot_box.candidates);
+            }
+            else if !args.quiet {
+                let totals = ballot_box.totals().into_iter().enumerate().collect();
+                reporting::current_count(totals, ballot_box.previous_totals(), &ballot_box.candidates, formatter, true);
+            }
+
+            return Err(Error::TooManyRounds(max_rounds));
+        }
+
+        if watch {
+            watch::draw(round, &ballot_box.totals(), &ballot_box.candidates, formatter);
+        }
+
+        if args.progress_rounds && !args.jsonl && !args.quiet {
+            let remaining = ballot_box.candidates.len() - ballot_box.eliminated().len();
+            reporting::progress(round, remaining, formatter);
+        }
+
+        reporting::round(round, formatter, report && !watch);
+
+        if args.trace_leader {
+            reporting::leader(round, &ballot_box.totals(), &ballot_box.candidates, formatter);
+        }
+
+        let status = ballot_box.status(config.threshold, config.tiebreak, &mut rng, formatter, report && !watch);
+
+        if let Some(audit_log) = audit_log.as_mut() {
+            audit_log.round(round, &ballot_box.totals(), &status)?;
+        }
+
+        if args.jsonl {
+            reporting::jsonl_round(round, &ballot_box.totals(), &status, &ballot_box.candidates);
+        }
+
+        if let Some(rounds_dir) = &args.rounds_dir {
+            let json = reporting::json_round(round, &ballot_box.totals(), &status, &ballot_box.candidates);
+            fs::write(rounds_dir.join(format!("round_{:03}.json", round)), json + "\n")?;
+        }
+
+        match status {
+            Winner(_) | Tie(_) => break status,
+            Runoff(to_eliminated) => {
+                eliminations.extend(to_eliminated.iter().map(|&c| (c, round)));
+                ballot_box.runoff(to_eliminated);
+            },
             Promotion(to_promote) => ballot_box.promote(to_promote),
         }
+
+        if watch {
+            watch::pause();
+        }
     };
 
-    reporting::winner(winner, &ballot_box.candidates);
+    if args.timings {
+        reporting::timing(Message::Counting, counting.elapsed(), formatter);
+    }
+
+    reporting::eliminations(&eliminations, &ballot_box.candidates, formatter, report);
+    reporting::transfer_levels(ballot_box.transfer_levels(), formatter, report);
+
+    if let Winner(winner) = result {
+        reporting::leader_overtaken(winner, &first_round, &ballot_box.candidates, formatter, report);
+    }
+
+    // The quorum is measured against every ballot read, including those which have exhausted.
+    let quorum = args.quorum.map(|quorum| (quorum * f64::from(ballot_box.ballots())).ceil() as u32);
+
+    let verdict = Verdict::of(&result, nota, quorum, &ballot_box.totals());
+
+    // Only cite the seed if it actually decided something.
+    let seed = rng.used().then_some(config.seed);
+
+    if args.jsonl {
+        reporting::jsonl_result(&result, verdict, &eliminations, seed, &ballot_box.candidates);
+    }
+    else if args.quiet {
+        reporting::standings_line(&result, verdict, &ballot_box.totals(), ballot_box.total_votes(), &ballot_box.candidates, formatter);
+    }
+    else {
+        // The report has already shown the final round.
+        let summary = args.summary_only && !report;
+        reporting::current_count(ballot_box.totals().into_iter().enumerate().collect(), ballot_box.previous_totals(), &ballot_box.candidates, formatter, summary);
+
+        reporting::winner(&result, verdict, &ballot_box.candidates, formatter);
+
+        if let (Winner(winner), Some(pairwise)) = (&result, &pairwise) {
+            reporting::condorcet(condorcet::standing(pairwise, *winner), formatter);
+        }
+
+        if let Some(seed) = seed {
+            reporting::seed(seed, formatter, false);
+        }
+    }
+
+    if let Some(manifest) = &args.manifest {
+        let inputs : Vec<(&path::Path, &str)> =
+            iter::once(&args.path)
+            .chain(&args.merge)
+            .map(path::PathBuf::as_path)
+            .zip(hashes.iter().map(String::as_str))
+            .collect();
+
+        let arguments : Vec<String> = env::args().collect();
+        let result = reporting::json_result(&result, verdict, &eliminations, seed, &ballot_box.candidates);
+
+        manifest::write(manifest, &inputs, &arguments, config.seed, &result)?;
+    }
+
+    // Like the rest of the text report, the warning would break the scripting modes' output.
+    if !args.quiet && !args.jsonl {
+        if let (Winner(winner), Some(recount_margin)) = (&result, args.recount_margin) {
+            reporting::recount_margin(ballot_box.margin(*winner), ballot_box.total_votes(), recount_margin, formatter);
+        }
+    }
     
-    Ok(())
+    Ok(verdict.exit_code())
 }
 
 fn main() {
-    let args = Args::parse();
+    let mut args = Args::parse();
+
+    // The JSON report is the same as the JSON lines output, and Markdown has no use for colour.
+    match args.report_format {
+        ReportFormat::Json => args.jsonl = true,
+        ReportFormat::Markdown => colored::control::set_override(false),
+        ReportFormat::Text => (),
+    }
+
+    let formatter = Formatter {
+        locale : args.locale,
+        lang : args.lang,
+        percent_decimals : args.percent_decimals,
+        by_index : args.by_index,
+        sort_count : args.sort_count,
+        table : args.table,
+        bars : args.bars,
+        deltas : args.deltas,
+        report_format : args.report_format,
+        name_width : args.name_width,
+    };
+
+    if args.watch_file {
+        loop {
+            file_watch::clear_screen();
+
+            // Errors are expected while the file is being edited, so report them and keep going.
+            if let Err(error) = count_contests(&args, &formatter) {
+                reporting::error(error, &formatter);
+            }
+
+            file_watch::wait_for_change(&args.path);
+        }
+    }
 
-    match count(args) {
-        Ok(_) => {
-            process::exit(exitcode::OK);
+    match count_contests(&args, &formatter) {
+        Ok(code) => {
+            process::exit(code);
         },
         Err(error) => {
-            reporting::csv_error(error);
-            process::exit(exitcode::DATAERR);
+            let code = error.exit_code();
+            reporting::error(error, &formatter);
+            process::exit(code);
         }
     }
 }
@@ -0,0 +1,29 @@
+use std::fs;
+use std::io;
+use std::path;
+
+use crate::json;
+
+/// Writes a JSON manifest to `path` tying the `result` of a count, already formatted as JSON, to
+/// the exact inputs and options it came from. Each input file is listed with the SHA-256 hash of
+/// the contents which were counted, taken as they were read, so anyone holding the files can check
+/// they are the ones that were counted, and the command line `arguments` together with the `seed`
+/// are enough to repeat the count.
+pub fn write(path : &path::Path, inputs : &[(&path::Path, &str)], arguments : &[String], seed : u64, result : &str) -> io::Result<()> {
+    let files =
+        inputs
+        .iter()
+        .map(|(input, hash)| format!("{{\"path\":{},\"sha256\":{}}}", json::string(&input.display().to_string()), json::string(hash)));
+
+    let arguments = json::array(arguments.iter().map(|argument| json::string(argument)));
+
+    let manifest = format!(
+        "{{\"inputs\":{},\"arguments\":{},\"seed\":{},\"result\":{}}}\n",
+        json::array(files),
+        arguments,
+        seed,
+        result,
+    );
+
+    fs::write(path, manifest)
+}
@@ -0,0 +1,287 @@
+use clap::ValueEnum;
+
+/// Language used for messages shown to the user.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum Lang {
+    /// English.
+    En,
+    /// French.
+    Fr,
+}
+
+/// Catalog of the messages shown to the user, translated by `Lang`.
+#[derive(Clone, Copy, Debug)]
+pub enum Message {
+    InvalidBallot,
+    Line,
+    CurrentCount,
+    Eliminating,
+    ResolvingTie,
+    Winner,
+    Tie,
+    TieBetween,
+    Warning,
+    ThresholdBelowRange,
+    ThresholdAboveRange,
+    CsvError,
+    IoError,
+    Round,
+    Seed,
+    RepeatedPreference,
+    NoPreferences,
+    TooFewPreferences,
+    PreferenceOutOfRange,
+    TooManyPreferences,
+    UnknownCandidate,
+    OnlyDroppedCandidates,
+    Divisors,
+    Seats,
+    GallagherIndex,
+    Statistics,
+    EffectiveCandidates,
+    Advisory,
+    LeaderOvertaken,
+    VerificationError,
+    CountsDiffered,
+    MismatchedCandidates,
+    TooFewBallots,
+    CountError,
+    TooManyRounds,
+    MissingFromTiebreakOrder,
+    MissingColumn,
+    HeaderLooksLikeBallot,
+    BulletVotes,
+    FullyRanked,
+    WithoutFirstPreferences,
+    Parsing,
+    Counting,
+    VotesNeeded,
+    WinningMargin,
+    Read,
+    ValidBallots,
+    TotalWeight,
+    Ignored,
+    TransferLevels,
+    LeadsWith,
+    LeadWith,
+    Inconclusive,
+    MustBeRerun,
+    NoQuorum,
+    BelowQuorum,
+    DryRun,
+    LooksCountable,
+    UnderRecountMargin,
+    StillCounting,
+    CandidatesRemaining,
+    ThresholdOutOfReach,
+    Eliminations,
+    InRound,
+    Contest,
+    CondorcetWinner,
+    CondorcetLoser,
+    NeitherCondorcet,
+    DuplicateRows,
+    ThresholdSweep,
+    NoWinner,
+    Abstentions,
+    Candidate,
+    Votes,
+    Share,
+    FullNames,
+    OptionError,
+    HasNoEffectWith,
+    Id,
+    TooManyCandidates,
+    KilobytesPerPreference,
+    Filtered,
+    NotAFraction,
+    CountStarted,
+}
+
+impl Message {
+    /// Returns the text of the message in the provided language.
+    pub fn text(&self, lang : Lang) -> &'static str {
+        match lang {
+            Lang::En => self.english(),
+            Lang::Fr => self.french(),
+        }
+    }
+
+    fn english(&self) -> &'static str {
+        match self {
+            Message::InvalidBallot => "Invalid Ballot:",
+            Message::Line => "line",
+            Message::CurrentCount => "Current Count:",
+            Message::Eliminating => "Eliminating:",
+            Message::ResolvingTie => "Resolving tie between:",
+            Message::Winner => "Winner:",
+            Message::Tie => "The election was a tie",
+            Message::TieBetween => "Tie between:",
+            Message::Warning => "Warning:",
+            Message::ThresholdBelowRange => "Threshold was below the allowed range, and set to 0",
+            Message::ThresholdAboveRange => "Threshold was above the allowed range, and set to 1",
+            Message::CsvError => "CSV Error:",
+            Message::IoError => "IO Error:",
+            Message::Round => "Round",
+            Message::Seed => "Seed:",
+            Message::RepeatedPreference => "- a preference was repeated",
+            Message::NoPreferences => "- no preferences were expressed",
+            Message::TooFewPreferences => "- fewer preferences than the minimum of",
+            Message::PreferenceOutOfRange => "- a preference was past the number of candidates",
+            Message::TooManyPreferences => "- more preferences than the number of candidates,",
+            Message::UnknownCandidate => "- a preference was for a candidate who doesn't exist",
+            Message::OnlyDroppedCandidates => "- every preference was for a dropped candidate",
+            Message::Divisors => "Divisors:",
+            Message::Seats => "Seats:",
+            Message::GallagherIndex => "Gallagher index:",
+            Message::Statistics => "Statistics:",
+            Message::EffectiveCandidates => "Effective number of candidates:",
+            Message::Advisory => "Advisory:",
+            Message::LeaderOvertaken => "possible non-monotonicity, as the first round was led by",
+            Message::VerificationError => "Verification Error:",
+            Message::CountsDiffered => "counting the ballots twice gave different results",
+            Message::MismatchedCandidates => "the ballot files being merged have different candidates",
+            Message::TooFewBallots => "fewer valid ballots were read than the minimum of",
+            Message::CountError => "Count Error:",
+            Message::TooManyRounds => "the count was abandoned after reaching the maximum number of rounds,",
+            Message::MissingFromTiebreakOrder => "the tiebreak order is missing the candidate",
+            Message::MissingColumn => "the ballot file has no column headed",
+            Message::HeaderLooksLikeBallot => "the first row of the ballot file looks like a ballot rather than a header of candidates",
+            Message::BulletVotes => "Bullet votes:",
+            Message::FullyRanked => "Fully ranked:",
+            Message::WithoutFirstPreferences => "Candidates without first preferences:",
+            Message::Parsing => "Parsing took",
+            Message::Counting => "Counting took",
+            Message::VotesNeeded => "Votes needed to win this round:",
+            Message::WinningMargin => "the winning margin of",
+            Message::Read => "Read",
+            Message::ValidBallots => "valid ballots",
+            Message::TotalWeight => "total weight",
+            Message::Ignored => "Ignored:",
+            Message::TransferLevels => "Transfers by preference landed on:",
+            Message::LeadsWith => "leads with",
+            Message::LeadWith => "lead with",
+            Message::Inconclusive => "Inconclusive:",
+            Message::MustBeRerun => "none of the above won, so the election must be rerun",
+            Message::NoQuorum => "No quorum:",
+            Message::BelowQuorum => "won the count with fewer votes than the quorum of",
+            Message::DryRun => "Dry run:",
+            Message::LooksCountable => "file looks countable",
+            Message::UnderRecountMargin => "is under the recount margin of",
+            Message::StillCounting => "Counting... round",
+            Message::CandidatesRemaining => "candidates remaining",
+            Message::ThresholdOutOfReach => "no candidate starts within reach of the threshold, so many rounds may be needed, as the leader has",
+            Message::Eliminations => "Eliminated:",
+            Message::InRound => "round",
+            Message::Contest => "Contest:",
+            Message::CondorcetWinner => "the winner is also the Condorcet winner, beating every other candidate head to head",
+            Message::CondorcetLoser => "the winner is the Condorcet loser, losing to every other candidate head to head",
+            Message::NeitherCondorcet => "the winner is neither the Condorcet winner nor the Condorcet loser",
+            Message::DuplicateRows => "duplicate ballot rows detected, which may have been entered twice:",
+            Message::ThresholdSweep => "Winner at each threshold:",
+            Message::NoWinner => "tie",
+            Message::Abstentions => "Abstentions:",
+            Message::Candidate => "Candidate",
+            Message::Votes => "Votes",
+            Message::Share => "Share",
+            Message::FullNames => "Full names:",
+            Message::OptionError => "Option Error:",
+            Message::HasNoEffectWith => "has no effect with",
+            Message::Id => "id",
+            Message::TooManyCandidates => "candidates in the ballot file, more than the maximum of",
+            Message::KilobytesPerPreference => "KB of memory could be used for each preference on a ballot",
+            Message::Filtered => "Left out by filters:",
+            Message::NotAFraction => "must be from 0.0 to 1.0, not",
+            Message::CountStarted => "ballots can't be added once counting has started",
+        }
+    }
+
+    fn french(&self) -> &'static str {
+        match self {
+            Message::InvalidBallot => "Bulletin invalide :",
+            Message::Line => "ligne",
+            Message::CurrentCount => "Décompte actuel :",
+            Message::Eliminating => "Élimination :",
+            Message::ResolvingTie => "Résolution de l'égalité entre :",
+            Message::Winner => "Vainqueur :",
+            Message::Tie => "L'élection s'est soldée par une égalité",
+            Message::TieBetween => "Égalité entre :",
+            Message::Warning => "Avertissement :",
+            Message::ThresholdBelowRange => "Le seuil était inférieur à la plage autorisée et a été fixé à 0",
+            Message::ThresholdAboveRange => "Le seuil était supérieur à la plage autorisée et a été fixé à 1",
+            Message::CsvError => "Erreur CSV :",
+            Message::IoError => "Erreur d'E/S :",
+            Message::Round => "Tour",
+            Message::Seed => "Graine :",
+            Message::RepeatedPreference => "- une préférence a été répétée",
+            Message::NoPreferences => "- aucune préférence n'a été exprimée",
+            Message::TooFewPreferences => "- moins de préférences que le minimum de",
+            Message::PreferenceOutOfRange => "- une préférence dépassait le nombre de candidats",
+            Message::TooManyPreferences => "- plus de préférences que le nombre de candidats,",
+            Message::UnknownCandidate => "- une préférence désignait un candidat inexistant",
+            Message::OnlyDroppedCandidates => "- toutes les préférences désignaient des candidats retirés",
+            Message::Divisors => "Diviseurs :",
+            Message::Seats => "Sièges :",
+            Message::GallagherIndex => "Indice de Gallagher :",
+            Message::Statistics => "Statistiques :",
+            Message::EffectiveCandidates => "Nombre effectif de candidats :",
+            Message::Advisory => "Avis :",
+            Message::LeaderOvertaken => "non-monotonie possible, car le premier tour était mené par",
+            Message::VerificationError => "Erreur de vérification :",
+            Message::CountsDiffered => "deux décomptes des bulletins ont donné des résultats différents",
+            Message::MismatchedCandidates => "les fichiers de bulletins fusionnés ont des candidats différents",
+            Message::TooFewBallots => "moins de bulletins valides ont été lus que le minimum de",
+            Message::CountError => "Erreur de décompte :",
+            Message::TooManyRounds => "le décompte a été abandonné après avoir atteint le nombre maximal de tours,",
+            Message::MissingFromTiebreakOrder => "l'ordre de départage ne contient pas le candidat",
+            Message::MissingColumn => "le fichier de bulletins n'a pas de colonne intitulée",
+            Message::HeaderLooksLikeBallot => "la première ligne du fichier de bulletins ressemble à un bulletin plutôt qu'à un en-tête de candidats",
+            Message::BulletVotes => "Votes uniques :",
+            Message::FullyRanked => "Classements complets :",
+            Message::WithoutFirstPreferences => "Candidats sans premières préférences :",
+            Message::Parsing => "Lecture :",
+            Message::Counting => "Décompte :",
+            Message::VotesNeeded => "Voix nécessaires pour gagner ce tour :",
+            Message::WinningMargin => "la marge de victoire de",
+            Message::Read => "Lu",
+            Message::ValidBallots => "bulletins valides",
+            Message::TotalWeight => "poids total",
+            Message::Ignored => "Ignorés :",
+            Message::TransferLevels => "Transferts selon la préférence atteinte :",
+            Message::LeadsWith => "mène avec",
+            Message::LeadWith => "mènent avec",
+            Message::Inconclusive => "Non concluant :",
+            Message::MustBeRerun => "« aucun des candidats » l'a emporté, l'élection doit donc être recommencée",
+            Message::NoQuorum => "Pas de quorum :",
+            Message::BelowQuorum => "a remporté le décompte avec moins de voix que le quorum de",
+            Message::DryRun => "Essai à blanc :",
+            Message::LooksCountable => "le fichier semble pouvoir être décompté",
+            Message::UnderRecountMargin => "est inférieure à la marge de recomptage de",
+            Message::StillCounting => "Décompte en cours... tour",
+            Message::CandidatesRemaining => "candidats restants",
+            Message::ThresholdOutOfReach => "aucun candidat ne part à portée du seuil, donc de nombreux tours pourraient être nécessaires, car le meneur a",
+            Message::Eliminations => "Éliminés :",
+            Message::InRound => "tour",
+            Message::Contest => "Scrutin :",
+            Message::CondorcetWinner => "le vainqueur est aussi le vainqueur de Condorcet, battant chaque autre candidat en duel",
+            Message::CondorcetLoser => "le vainqueur est le perdant de Condorcet, battu par chaque autre candidat en duel",
+            Message::NeitherCondorcet => "le vainqueur n'est ni le vainqueur ni le perdant de Condorcet",
+            Message::DuplicateRows => "lignes de bulletins en double détectées, peut-être saisies deux fois :",
+            Message::ThresholdSweep => "Vainqueur à chaque seuil :",
+            Message::NoWinner => "égalité",
+            Message::Abstentions => "Abstentions :",
+            Message::Candidate => "Candidat",
+            Message::Votes => "Voix",
+            Message::Share => "Part",
+            Message::FullNames => "Noms complets :",
+            Message::OptionError => "Erreur d'option :",
+            Message::HasNoEffectWith => "n'a aucun effet avec",
+            Message::Id => "id",
+            Message::TooManyCandidates => "candidats dans le fichier de bulletins, soit plus que le maximum de",
+            Message::KilobytesPerPreference => "Ko de mémoire pourraient être utilisés pour chaque préférence d'un bulletin",
+            Message::Filtered => "Écartés par les filtres :",
+            Message::NotAFraction => "doit être entre 0,0 et 1,0, et non",
+            Message::CountStarted => "impossible d'ajouter des bulletins une fois le dépouillement commencé",
+        }
+    }
+}
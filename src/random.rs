@@ -0,0 +1,60 @@
+use std::process;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Seedable pseudo-random number generator (SplitMix64) used for every random decision in a
+/// count. The same seed always produces the same sequence, so the same seed and the same input
+/// always give identical results.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state : u64,
+    used : bool,
+}
+
+impl Rng {
+    /// Creates a new generator from the provided seed.
+    pub fn from_seed(seed : u64) -> Self {
+        Rng {
+            state : seed,
+            used : false,
+        }
+    }
+
+    /// Returns whether any random decision has been drawn from the generator.
+    pub fn used(&self) -> bool {
+        self.used
+    }
+
+    /// Returns the next value in the sequence.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a uniformly distributed value in `0..bound`.
+    pub fn below(&mut self, bound : usize) -> usize {
+        self.used = true;
+
+        let bound = bound as u64;
+
+        // Reject values from the incomplete final block of the range to avoid modulo bias.
+        let zone = u64::MAX - (u64::MAX % bound);
+        loop {
+            let value = self.next_u64();
+            if value < zone {
+                break (value % bound) as usize;
+            }
+        }
+    }
+}
+
+/// Derives a seed from the system clock and process, for when the user doesn't supply one.
+pub fn system_seed() -> u64 {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+
+    // Mix through the generator so that similar times give unrelated seeds.
+    Rng::from_seed(now.as_nanos() as u64 ^ u64::from(process::id())).next_u64()
+}
@@ -1,13 +1,25 @@
+use std::time::Duration;
+
 use colored::*;
 
 use crate::ballot_box::{
+    self,
     CountStatus,
     CountStatus::*
 };
+use crate::allocation::{Allocation, Party};
+use crate::ballot::InvalidReason;
 use crate::candidates::Candidates;
+use crate::condorcet::Standing;
+use crate::election::Verdict;
+use crate::error::Error;
+use crate::formatting::{self, Formatter, ReportFormat};
+use crate::json;
+use crate::messages::Message;
+use crate::stats::Statistics;
 
-/// Displays the invalid ballot provided.
-pub fn invalid_ballot(number : u32, ballot : &[Option<usize>], report : bool) {
+/// Displays the invalid ballot provided, and why it is invalid, along with its ID if it has one.
+pub fn invalid_ballot(number : u32, id : Option<&str>, ballot : &[Option<usize>], reason : InvalidReason, formatter : &Formatter, report : bool) {
     if report {
         let segments : Vec<_> =
             ballot
@@ -21,57 +33,594 @@ pub fn invalid_ballot(number : u32, ballot : &[Option<usize>], report : bool) {
             .collect();
 
         let formatted = segments.join(",");
-        println!("{} {} (line: {})", "Invalid Ballot:".bright_green().bold(), formatted, number);
+
+        let reason = match reason {
+            InvalidReason::RepeatedPreference => String::from(formatter.text(Message::RepeatedPreference)),
+            InvalidReason::NoPreferences => String::from(formatter.text(Message::NoPreferences)),
+            InvalidReason::TooFewPreferences(min) => format!("{} {}", formatter.text(Message::TooFewPreferences), min),
+            InvalidReason::PreferenceOutOfRange => String::from(formatter.text(Message::PreferenceOutOfRange)),
+            InvalidReason::TooManyPreferences(max) => format!("{} {}", formatter.text(Message::TooManyPreferences), max),
+            InvalidReason::UnknownCandidate => String::from(formatter.text(Message::UnknownCandidate)),
+            InvalidReason::OnlyDroppedCandidates => String::from(formatter.text(Message::OnlyDroppedCandidates)),
+        };
+
+        let location = match id {
+            Some(id) => format!("{}: {}, {}: {}", formatter.text(Message::Line), number, formatter.text(Message::Id), id.trim()),
+            None => format!("{}: {}", formatter.text(Message::Line), number),
+        };
+
+        line(formatter, format!("{} {} ({}) {}", formatter.text(Message::InvalidBallot).bright_green().bold(), formatted, location, reason));
     }
 }
 
-/// Displays the current count of top preference votes.   
-pub fn current_count(count : Vec<(usize, u32)>, candidates : &Candidates, report : bool) {
+/// Displays how many valid ballots were read, and the total weight of the votes they carry.
+pub fn ballots_read(ballots : u32, total_votes : u32, formatter : &Formatter, report : bool) {
     if report {
-        println!("{}", "Current Count:".bright_yellow().bold());
+        line(formatter, format!(
+            "{} {} {} ({} {})",
+            formatter.text(Message::Read).bright_yellow().bold(),
+            formatter.number(ballots),
+            formatter.text(Message::ValidBallots),
+            formatter.text(Message::TotalWeight),
+            formatter.number(total_votes),
+        ));
+    }
+}
 
-        for (candidate, votes) in count {
-            println!("    {} : {}", candidates.get(candidate).unwrap(), votes);
+/// Displays the number of formal abstentions read.
+pub fn abstentions(abstentions : u32, formatter : &Formatter, report : bool) {
+    if report {
+        line(formatter, format!("{} {}", formatter.text(Message::Abstentions).bright_yellow().bold(), formatter.number(abstentions)));
+    }
+}
+
+/// Displays the number of valid ballots left out of the count by filters, if any filters were given.
+pub fn filtered(filtered : u32, formatter : &Formatter, report : bool) {
+    if report {
+        line(formatter, format!("{} {}", formatter.text(Message::Filtered).bright_yellow().bold(), formatter.number(filtered)));
+    }
+}
+
+/// Displays the candidates whose columns were dropped from the file, if any.
+pub fn ignored(dropped : &[String], formatter : &Formatter, report : bool) {
+    if report && !dropped.is_empty() {
+        line(formatter, format!("{} {}", formatter.text(Message::Ignored).bright_yellow().bold(), dropped.join(", ")));
+    }
+}
+
+/// Displays the full name of each candidate whose name is cut short in the report.
+pub fn name_legend(candidates : &Candidates, formatter : &Formatter, report : bool) {
+    let shortened : Vec<usize> =
+        (0..candidates.len())
+        .filter(|&c| formatter.candidate(c, candidates) != formatter.candidate_in_full(c, candidates))
+        .collect();
+
+    if report && !shortened.is_empty() {
+        let mut legend = formatter.text(Message::FullNames).bright_yellow().bold().to_string();
+
+        for candidate in shortened {
+            legend.push_str(&format!("\n    {} : {}", formatter.candidate(candidate, candidates), formatter.candidate_in_full(candidate, candidates)));
         }
+
+        line(formatter, legend);
+    }
+}
+
+/// Labels the count of one contest in a file holding several, as a line of JSON with `jsonl`, as a
+/// tab separated `CONTEST<TAB>name` line with `quiet`, and as a heading otherwise.
+pub fn contest(name : &str, jsonl : bool, quiet : bool, formatter : &Formatter) {
+    if jsonl {
+        println!("{{\"contest\":{}}}", json::string(name));
+    }
+    else if quiet {
+        println!("CONTEST\t{}", name);
+    }
+    else {
+        println!("{} {}", formatter.text(Message::Contest).bright_magenta().bold(), name);
+    }
+}
+
+/// Displays the current count of top preference votes, along with the change from the `previous`
+/// round's totals if asked for.
+pub fn current_count(mut count : Vec<(usize, u32)>, previous : Option<&[u32]>, candidates : &Candidates, formatter : &Formatter, report : bool) {
+    if report {
+        count.sort_by_key(|(candidate, _)| candidates.position(*candidate));
+
+        // The sort is stable, so candidates on equal votes stay in the order they are displayed.
+        if formatter.sort_count {
+            count.sort_by(|(_, v1), (_, v2)| v2.cmp(v1));
+        }
+
+        let total : u32 = count.iter().map(|(_, votes)| votes).sum();
+        let max = count.iter().map(|(_, votes)| *votes).max().unwrap_or(0);
+
+        // Bars trail each line, and are left empty when they aren't shown.
+        let bars : Vec<String> =
+            count
+            .iter()
+            .map(|(_, votes)| match formatting::bar(*votes, max) {
+                bar if formatter.bars && !bar.is_empty() => format!(" {}", bar),
+                _ => String::new(),
+            })
+            .collect();
+
+        // Votes are shown with their change since the round before, if there was one.
+        let show_votes = |candidate : usize, votes : u32| match previous {
+            Some(previous) if formatter.deltas => format!("{} ({})", formatter.number(votes), formatter.change(previous[candidate], votes)),
+            _ => formatter.number(votes),
+        };
+
+        if formatter.report_format == ReportFormat::Markdown {
+            markdown_count(&count, total, &show_votes, candidates, formatter);
+            return;
+        }
+
+        println!("{}", formatter.text(Message::CurrentCount).bright_yellow().bold());
+
+        if formatter.table {
+            let rows : Vec<[String; 3]> =
+                count
+                .into_iter()
+                .map(|(candidate, votes)| [formatter.candidate(candidate, candidates), show_votes(candidate, votes), formatter.percent(votes, total)])
+                .collect();
+
+            table(&rows, &bars);
+        }
+        else {
+            for ((candidate, votes), bar) in count.into_iter().zip(bars) {
+                println!("    {} : {} ({}){}", formatter.candidate(candidate, candidates), show_votes(candidate, votes), formatter.percent(votes, total), bar);
+            }
+        }
+    }
+}
+
+/// Prints the count as a Markdown table.
+fn markdown_count(count : &[(usize, u32)], total : u32, show_votes : &dyn Fn(usize, u32) -> String, candidates : &Candidates, formatter : &Formatter) {
+    println!("| {} | {} | {} |", formatter.text(Message::Candidate), formatter.text(Message::Votes), formatter.text(Message::Share));
+    println!("| --- | ---: | ---: |");
+
+    for &(candidate, votes) in count {
+        // A pipe in a name would otherwise end its cell early.
+        let name = formatter.candidate(candidate, candidates).replace('|', "\\|");
+        println!("| {} | {} | {} |", name, show_votes(candidate, votes), formatter.percent(votes, total));
+    }
+
+    println!();
+}
+
+/// Displays the heading of a round, which is only shown in the Markdown report.
+pub fn round(round : usize, formatter : &Formatter, report : bool) {
+    if report && formatter.report_format == ReportFormat::Markdown {
+        println!("## {} {}\n", formatter.text(Message::Round), round);
+    }
+}
+
+/// Prints a line of the report. In Markdown each line is its own paragraph, so that lines aren't
+/// run together.
+fn line(formatter : &Formatter, text : String) {
+    match formatter.report_format {
+        ReportFormat::Markdown => println!("{}\n", text),
+        _ => println!("{}", text),
+    }
+}
+
+/// Prints rows of a name followed by numbers as a table with borders, with the names padded to
+/// the same width and the numbers right aligned. Each row is followed by its `suffix`.
+fn table(rows : &[[String; 3]], suffixes : &[String]) {
+    let mut widths = [0; 3];
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let border = format!("    +{}+", widths.iter().map(|width| "-".repeat(width + 2)).collect::<Vec<String>>().join("+"));
+
+    println!("{}", border);
+    for ([name, votes, percent], suffix) in rows.iter().zip(suffixes) {
+        println!("    | {:<w0$} | {:>w1$} | {:>w2$} |{}", name, votes, percent, suffix, w0 = widths[0], w1 = widths[1], w2 = widths[2]);
+    }
+    println!("{}", border);
+}
+
+/// Displays a single line naming the leader of a round and their share of the votes.
+pub fn leader(round : usize, totals : &[u32], candidates : &Candidates, formatter : &Formatter) {
+    let total : u32 = totals.iter().sum();
+    let max = totals.iter().copied().max().unwrap_or(0);
+
+    let mut leaders : Vec<usize> = (0..totals.len()).filter(|&c| totals[c] == max).collect();
+    leaders.sort_by_key(|&c| candidates.position(c));
+
+    let verb = match leaders.len() {
+        1 => Message::LeadsWith,
+        _ => Message::LeadWith,
+    };
+
+    let leaders = leaders.iter().map(|&c| formatter.candidate(c, candidates)).collect::<Vec<String>>().join(", ");
+
+    println!("{} {}: {} {} {}", formatter.text(Message::Round).bright_yellow().bold(), round, leaders, formatter.text(verb), formatter.percent(max, total));
+}
+
+/// Displays (to stderr) which round the count has reached, so that long counts show signs of life.
+pub fn progress(round : usize, remaining : usize, formatter : &Formatter) {
+    eprintln!("{} {}, {} {}", formatter.text(Message::StillCounting).bright_black(), round, remaining, formatter.text(Message::CandidatesRemaining).bright_black());
+}
+
+/// Displays how many votes are needed to win in the current round.
+pub fn votes_needed(needed : u32, formatter : &Formatter, report : bool) {
+    if report {
+        line(formatter, format!("{} {}", formatter.text(Message::VotesNeeded), formatter.number(needed)));
     }
 }
 
 /// Displays a `CountStatus` and associated data if it is a `Runoff` or `Promotion`.
-pub fn status(status : &CountStatus, candidates : &Candidates, report : bool) {
+pub fn status(status : &CountStatus, candidates : &Candidates, formatter : &Formatter, report : bool) {
     if report {
         match status {
             Runoff(to_distribute) => {
-                let candidates = to_distribute.iter().map(|c| candidates.get(*c).unwrap().clone()).collect::<Vec<String>>().join(", ");
-                println!("{} {}", "Eliminating:".bright_magenta(), candidates);
+                let candidates = to_distribute.iter().map(|c| formatter.candidate(*c, candidates)).collect::<Vec<String>>().join(", ");
+                line(formatter, format!("{} {}", formatter.text(Message::Eliminating).bright_magenta(), candidates));
             },
             Promotion(to_promote) => {
-                let candidates = to_promote.iter().map(|c| candidates.get(*c).unwrap().clone()).collect::<Vec<String>>().join(", ");
-                println!("Resolving tie between: {}", candidates.bright_cyan());
+                let candidates = to_promote.iter().map(|c| formatter.candidate(*c, candidates)).collect::<Vec<String>>().join(", ");
+                line(formatter, format!("{} {}", formatter.text(Message::ResolvingTie), candidates.bright_cyan()));
             },
             _ => (),
         }
     }
 }
 
-/// Displays the winner.
-pub fn winner(winner : Option<usize>, candidates : &Candidates) {
-    match winner {
-        Some(winner) => println!("{} {}", "Winner:".bright_blue(), candidates.get(winner).unwrap()),
-        None => println!("{}", "The election was a tie".bright_blue()),
+/// Formats the `index` and `name` fields identifying a candidate in a JSON object, leaving the
+/// braces to the caller so more fields can follow. Both are always given, whatever `--by-index`
+/// is set to.
+fn json_candidate(candidate : usize, candidates : &Candidates) -> String {
+    format!("\"index\":{},\"name\":{}", candidate, json::string(candidates.get(candidate).unwrap()))
+}
+
+/// Formats a list of candidates as a JSON array of objects with their index and name.
+fn json_candidates(list : &[usize], candidates : &Candidates) -> String {
+    json::array(list.iter().map(|&c| format!("{{{}}}", json_candidate(c, candidates))))
+}
+
+/// Writes a round's totals and the decision made from them as a single line of JSON.
+pub fn jsonl_round(round : usize, totals : &[u32], status : &CountStatus, candidates : &Candidates) {
+    println!("{}", json_round(round, totals, status, candidates));
+}
+
+/// Formats each candidate's total as a JSON array of objects.
+fn json_totals(totals : &[u32], candidates : &Candidates) -> String {
+    json::array(
+        totals
+        .iter()
+        .enumerate()
+        .map(|(c, votes)| format!("{{{},\"votes\":{}}}", json_candidate(c, candidates), votes))
+    )
+}
+
+/// Formats the candidates eliminated so far, with the round each was eliminated in, as a JSON
+/// array of objects.
+fn json_eliminations(eliminations : &[(usize, usize)], candidates : &Candidates) -> String {
+    json::array(
+        eliminations
+        .iter()
+        .map(|&(c, round)| format!("{{{},\"round\":{}}}", json_candidate(c, candidates), round))
+    )
+}
+
+/// Formats a round's totals and the decision made from them as a JSON object.
+pub fn json_round(round : usize, totals : &[u32], status : &CountStatus, candidates : &Candidates) -> String {
+    let totals = json_totals(totals, candidates);
+
+    let (decision, listed) = match status {
+        Winner(winner) => ("winner", vec![*winner]),
+        Tie(tied) => ("tie", tied.clone()),
+        Runoff(to_eliminate) => ("runoff", to_eliminate.clone()),
+        Promotion(to_promote) => ("promotion", to_promote.clone()),
+    };
+
+    format!(
+        "{{\"round\":{},\"totals\":{},\"decision\":{},\"candidates\":{}}}",
+        round,
+        totals,
+        json::string(decision),
+        json_candidates(&listed, candidates),
+    )
+}
+
+/// Writes the final result of the count as a single line of JSON.
+pub fn jsonl_result(result : &CountStatus, verdict : Verdict, eliminations : &[(usize, usize)], seed : Option<u64>, candidates : &Candidates) {
+    println!("{}", json_result(result, verdict, eliminations, seed, candidates));
+}
+
+/// Formats the result of the count as JSON, as printed by `jsonl_result`.
+pub fn json_result(result : &CountStatus, verdict : Verdict, eliminations : &[(usize, usize)], seed : Option<u64>, candidates : &Candidates) -> String {
+    let (outcome, listed) = match result {
+        Winner(winner) => match verdict {
+            Verdict::Stands => ("winner", vec![*winner]),
+            Verdict::Inconclusive => ("inconclusive", vec![*winner]),
+            Verdict::NoQuorum(_) => ("no_quorum", vec![*winner]),
+        },
+        Tie(tied) => ("tie", tied.clone()),
+        _ => ("tie", Vec::new()),
+    };
+
+    // The seed is only included when a random decision was actually made.
+    let seed = seed.map(|seed| format!(",\"seed\":{}", seed)).unwrap_or_default();
+
+    format!("{{\"result\":{},\"candidates\":{},\"eliminated\":{}{}}}", json::string(outcome), json_candidates(&listed, candidates), json_eliminations(eliminations, candidates), seed)
+}
+
+/// Writes the state of a count abandoned after `--max-rounds` as the result line of JSON, in place
+/// of a winner or a tie.
+pub fn jsonl_stopped(totals : &[u32], eliminations : &[(usize, usize)], candidates : &Candidates) {
+    println!(
+        "{{\"result\":\"stopped\",\"stopped\":\"max_rounds\",\"totals\":{},\"eliminated\":{}}}",
+        json_totals(totals, candidates),
+        json_eliminations(eliminations, candidates),
+    );
+}
+
+/// Displays the statistics of the ballots.
+pub fn statistics(statistics : &Statistics, formatter : &Formatter) {
+    println!("{}", formatter.text(Message::Statistics).bright_yellow().bold());
+
+    if let Some(effective_candidates) = statistics.effective_candidates {
+        println!("    {} {}", formatter.text(Message::EffectiveCandidates), formatter.decimal(effective_candidates));
+    }
+
+    println!("    {} {} ({})", formatter.text(Message::BulletVotes), formatter.number(statistics.bullet_votes), formatter.percent(statistics.bullet_votes, statistics.total_votes));
+    println!("    {} {} ({})", formatter.text(Message::FullyRanked), formatter.number(statistics.fully_ranked), formatter.percent(statistics.fully_ranked, statistics.total_votes));
+    println!("    {} {}", formatter.text(Message::WithoutFirstPreferences), statistics.without_first_preferences);
+}
+
+/// Displays how many transferred votes landed on each preference.
+pub fn transfer_levels(levels : &[u32], formatter : &Formatter, report : bool) {
+    if report && levels.iter().any(|&votes| votes > 0) {
+        let levels =
+            levels
+            .iter()
+            .enumerate()
+            .filter(|(_, &votes)| votes > 0)
+            .map(|(level, &votes)| format!("{} : {}", level, formatter.number(votes)))
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        line(formatter, format!("{} {}", formatter.text(Message::TransferLevels).bright_yellow().bold(), levels));
+    }
+}
+
+/// Displays each eliminated candidate along with the round they were eliminated in.
+pub fn eliminations(eliminations : &[(usize, usize)], candidates : &Candidates, formatter : &Formatter, report : bool) {
+    if report && !eliminations.is_empty() {
+        let eliminations =
+            eliminations
+            .iter()
+            .map(|&(candidate, round)| format!("{} ({} {})", formatter.candidate(candidate, candidates), formatter.text(Message::InRound), round))
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        line(formatter, format!("{} {}", formatter.text(Message::Eliminations).bright_yellow().bold(), eliminations));
+    }
+}
+
+/// Displays an advisory if the winner was not a first round leader, since it is a sign that the
+/// count may have behaved non-monotonically. This is only a heuristic: a comeback on preferences
+/// is often perfectly monotonic.
+pub fn leader_overtaken(winner : usize, first_round : &[u32], candidates : &Candidates, formatter : &Formatter, report : bool) {
+    let max = first_round.iter().copied().max().unwrap_or(0);
+
+    if report && first_round[winner] < max {
+        let leaders =
+            first_round
+            .iter()
+            .enumerate()
+            .filter(|(_, &votes)| votes == max)
+            .map(|(c, _)| formatter.candidate(c, candidates))
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        line(formatter, format!("{} {} {}", formatter.text(Message::Advisory).yellow().bold(), formatter.text(Message::LeaderOvertaken), leaders));
+    }
+}
+
+/// Notes when the first round leader has less than half of the votes needed to win, which suggests
+/// a long count or a mistyped threshold.
+pub fn threshold_out_of_reach(first_round : &[u32], total_votes : u32, threshold : f64, formatter : &Formatter, report : bool) {
+    let max = first_round.iter().copied().max().unwrap_or(0);
+    let needed = (threshold * f64::from(total_votes)).ceil() as u32;
+
+    if report && 2 * max < needed {
+        line(formatter, format!("{} {} {}", formatter.text(Message::Advisory).yellow().bold(), formatter.text(Message::ThresholdOutOfReach), formatter.percent(max, total_votes)));
+    }
+}
+
+/// Displays the winner of the count at each threshold, as a percentage of the votes.
+pub fn threshold_sweep(results : &[(f64, Option<usize>)], candidates : &Candidates, formatter : &Formatter) {
+    println!("{}", formatter.text(Message::ThresholdSweep).bright_yellow().bold());
+
+    for (threshold, winner) in results {
+        let winner = match winner {
+            Some(winner) => formatter.candidate(*winner, candidates),
+            None => String::from(formatter.text(Message::NoWinner)),
+        };
+
+        println!("    {}% : {}", formatter.decimal(threshold * 100.0), winner);
+    }
+}
+
+/// Displays an advisory on whether the winner is also the Condorcet winner or loser.
+pub fn condorcet(standing : Standing, formatter : &Formatter) {
+    let verdict = match standing {
+        Standing::Winner => Message::CondorcetWinner,
+        Standing::Loser => Message::CondorcetLoser,
+        Standing::Neither => Message::NeitherCondorcet,
+    };
+
+    line(formatter, format!("{} {}", formatter.text(Message::Advisory).yellow().bold(), formatter.text(verdict)));
+}
+
+/// Prints the result as a single tab separated line for scripts, which is either
+/// `WINNER<TAB>name<TAB>votes<TAB>total`, with `INCONCLUSIVE` or `NO_QUORUM` in place of `WINNER`
+/// when the winner isn't elected, or `TIE<TAB>names` with the names separated by commas.
+pub fn standings_line(result : &CountStatus, verdict : Verdict, totals : &[u32], total_votes : u32, candidates : &Candidates, formatter : &Formatter) {
+    match result {
+        Winner(winner) => {
+            let status = match verdict {
+                Verdict::Stands => "WINNER",
+                Verdict::Inconclusive => "INCONCLUSIVE",
+                Verdict::NoQuorum(_) => "NO_QUORUM",
+            };
+            println!("{}\t{}\t{}\t{}", status, formatter.candidate_in_full(*winner, candidates), totals[*winner], total_votes);
+        },
+        Tie(tied) => {
+            let tied = tied.iter().map(|&c| formatter.candidate_in_full(c, candidates)).collect::<Vec<String>>().join(",");
+            println!("TIE\t{}", tied);
+        },
+        _ => println!("TIE\t"),
+    }
+}
+
+/// Displays that a dry run found nothing stopping the ballots from being counted.
+pub fn dry_run(formatter : &Formatter) {
+    println!("{} {}", formatter.text(Message::DryRun).bright_blue(), formatter.text(Message::LooksCountable));
+}
+
+/// Displays a warning if the winning margin, as a percentage of the votes in the final round, is
+/// below the margin at which a recount should be considered.
+pub fn recount_margin(margin : u32, total_votes : u32, recount_margin : f64, formatter : &Formatter) {
+    let percentage = match total_votes {
+        0 => 0.0,
+        _ => f64::from(margin) * 100.0 / f64::from(total_votes),
+    };
+
+    if percentage < recount_margin {
+        println!(
+            "{} {} {} ({}) {} {}%",
+            formatter.text(Message::Warning).yellow().bold(),
+            formatter.text(Message::WinningMargin),
+            formatter.number(margin),
+            formatter.percent(margin, total_votes),
+            formatter.text(Message::UnderRecountMargin),
+            formatter.decimal(recount_margin),
+        );
+    }
+}
+
+/// Displays the winner, or the tied candidates, from the final status of the count.
+pub fn winner(result : &CountStatus, verdict : Verdict, candidates : &Candidates, formatter : &Formatter) {
+    match result {
+        Winner(_) if verdict == Verdict::Inconclusive => line(formatter, format!("{} {}", formatter.text(Message::Inconclusive).bright_blue(), formatter.text(Message::MustBeRerun))),
+        Winner(winner) => match verdict {
+            Verdict::NoQuorum(quorum) => line(formatter, format!("{} {} {} {}", formatter.text(Message::NoQuorum).bright_blue(), formatter.candidate(*winner, candidates), formatter.text(Message::BelowQuorum), formatter.number(quorum))),
+            _ => line(formatter, format!("{} {}", formatter.text(Message::Winner).bright_blue(), formatter.candidate(*winner, candidates))),
+        },
+        Tie(tied) if !tied.is_empty() => {
+            let tied =
+                tied
+                .iter()
+                .map(|&c| formatter.candidate(c, candidates))
+                .collect::<Vec<String>>()
+                .join(", ");
+
+            line(formatter, format!("{} {}", formatter.text(Message::TieBetween).bright_blue(), tied));
+        },
+        _ => line(formatter, format!("{}", formatter.text(Message::Tie).bright_blue())),
+    }
+}
+
+/// Warns about rows of the file which exactly repeated an earlier row.
+pub fn duplicate_rows(duplicates : u32, formatter : &Formatter, report : bool) {
+    if report && duplicates > 0 {
+        line(formatter, format!("{} {} {}", formatter.text(Message::Warning).yellow().bold(), formatter.text(Message::DuplicateRows), formatter.number(duplicates)));
+    }
+}
+
+/// Warns that the first row of the file looks like a ballot, so the file may have no header row.
+pub fn header_looks_like_ballot(formatter : &Formatter, report : bool) {
+    if report {
+        line(formatter, format!("{} {}", formatter.text(Message::Warning).yellow().bold(), formatter.text(Message::HeaderLooksLikeBallot)));
     }
 }
 
 /// Notifies the user if the threshold was adjusted.
-pub fn threshold_squash(prev_threshold : f64) {
+pub fn threshold_squash(prev_threshold : f64, formatter : &Formatter, report : bool) {
+    if !report {
+        return;
+    }
+
     if prev_threshold < 0.0 {
-        println!("{} Threshold was below the allowed range, and set to 0", "Warning:".yellow().bold())
+        println!("{} {}", formatter.text(Message::Warning).yellow().bold(), formatter.text(Message::ThresholdBelowRange))
     }
     else if prev_threshold > 1.0 {
-        println!("{} Threshold was above the allowed range, and set to 1", "Warning:".yellow().bold())
+        println!("{} {}", formatter.text(Message::Warning).yellow().bold(), formatter.text(Message::ThresholdAboveRange))
+    }
+}
+
+/// Displays the seats allocated to each party, and the divisors used to allocate them.
+pub fn allocation(parties : &[Party], allocation : &Allocation, formatter : &Formatter) {
+    let divisors = allocation.divisors.iter().map(|d| d.to_string()).collect::<Vec<String>>().join(", ");
+    println!("{} {}", formatter.text(Message::Divisors).bright_yellow().bold(), divisors);
+
+    println!("{}", formatter.text(Message::Seats).bright_blue());
+    for (party, seats) in parties.iter().zip(&allocation.seats) {
+        println!("    {} : {} ({})", party.name, seats, formatter.number(party.votes));
+    }
+}
+
+/// Prints the seats allocated to each party as a line of JSON, along with the Gallagher index of the
+/// allocation.
+pub fn jsonl_allocation(parties : &[Party], allocation : &Allocation, gallagher : f64) {
+    let seats = json::array(
+        parties
+        .iter()
+        .zip(&allocation.seats)
+        .map(|(party, seats)| format!("{{\"party\":{},\"votes\":{},\"seats\":{}}}", json::string(&party.name), party.votes, seats))
+    );
+
+    let divisors = json::array(allocation.divisors.iter().map(|d| d.to_string()));
+
+    println!("{{\"allocation\":{},\"divisors\":{},\"gallagher\":{}}}", seats, divisors, gallagher);
+}
+
+/// Displays the Gallagher index of disproportionality of an allocation.
+pub fn gallagher(index : f64, formatter : &Formatter) {
+    println!("{} {}", formatter.text(Message::GallagherIndex).bright_blue(), formatter.decimal(index));
+}
+
+/// Displays the seed random decisions are drawn from, so that the count can be reproduced. With
+/// `stderr` it is written there instead, keeping it out of output meant for other programs.
+pub fn seed(seed : u64, formatter : &Formatter, stderr : bool) {
+    if stderr {
+        eprintln!("{} {}", formatter.text(Message::Seed).bright_blue(), seed);
+    }
+    else {
+        println!("{} {}", formatter.text(Message::Seed).bright_blue(), seed);
     }
 }
 
-/// Displays a CSV error.
-pub fn csv_error(error : csv::Error) {
-    println!("{} {}", "CSV Error:".red().bold(), error);
+/// Displays how long a phase of the count took, on stderr so as not to mix with the results.
+pub fn timing(phase : Message, duration : Duration, formatter : &Formatter) {
+    eprintln!("{} {:?}", formatter.text(phase).bright_black(), duration);
+}
+
+/// Displays an error which stopped the count.
+pub fn error(error : Error, formatter : &Formatter) {
+    match error {
+        Error::Csv(error) => println!("{} {}", formatter.text(Message::CsvError).red().bold(), error),
+        Error::Io(error) => println!("{} {}", formatter.text(Message::IoError).red().bold(), error),
+        Error::VerificationFailed => println!("{} {}", formatter.text(Message::VerificationError).red().bold(), formatter.text(Message::CountsDiffered)),
+        Error::MismatchedCandidates => println!("{} {}", formatter.text(Message::CsvError).red().bold(), formatter.text(Message::MismatchedCandidates)),
+        Error::TooFewBallots(minimum) => println!("{} {} {}", formatter.text(Message::CsvError).red().bold(), formatter.text(Message::TooFewBallots), formatter.number(minimum)),
+        Error::MissingFromTiebreakOrder(candidate) => println!("{} {} {}", formatter.text(Message::CsvError).red().bold(), formatter.text(Message::MissingFromTiebreakOrder), candidate),
+        Error::MissingColumn(column) => println!("{} {} {}", formatter.text(Message::CsvError).red().bold(), formatter.text(Message::MissingColumn), column),
+        Error::MissingHeader => println!("{} {}", formatter.text(Message::CsvError).red().bold(), formatter.text(Message::HeaderLooksLikeBallot)),
+        Error::TooManyCandidates(found, max) => {
+            let kilobytes = u32::try_from(ballot_box::node_size(found) / 1024).unwrap_or(u32::MAX);
+            println!("{} {} {} {} ({} {})", formatter.text(Message::CsvError).red().bold(), found, formatter.text(Message::TooManyCandidates), max, formatter.number(kilobytes), formatter.text(Message::KilobytesPerPreference));
+        },
+        Error::IncompatibleOptions(option, other) => println!("{} {} {} {}", formatter.text(Message::OptionError).red().bold(), option, formatter.text(Message::HasNoEffectWith), other),
+        Error::NotAFraction(option, value) => println!("{} {} {} {}", formatter.text(Message::OptionError).red().bold(), option, formatter.text(Message::NotAFraction), value),
+        Error::CountStarted => println!("{} {}", formatter.text(Message::CountError).red().bold(), formatter.text(Message::CountStarted)),
+        Error::TooManyRounds(max) => println!("{} {} {}", formatter.text(Message::CountError).red().bold(), formatter.text(Message::TooManyRounds), max),
+    }
 }
@@ -0,0 +1,74 @@
+use crate::ballot_box::BallotBox;
+
+/// Summary statistics of the ballots, taken before counting begins.
+#[derive(Debug, Clone)]
+pub struct Statistics {
+    /// Laakso-Taagepera effective number of candidates, or `None` if there were no votes.
+    pub effective_candidates : Option<f64>,
+    /// Number of votes which only expressed a first preference.
+    pub bullet_votes : u32,
+    /// Number of votes which expressed a preference for every candidate.
+    pub fully_ranked : u32,
+    /// Number of candidates who received no first preferences.
+    pub without_first_preferences : usize,
+    /// Total number of votes.
+    pub total_votes : u32,
+}
+
+impl Statistics {
+    /// Calculates the statistics of a ballot box which has yet to be counted.
+    pub fn from_ballot_box(ballot_box : &BallotBox) -> Self {
+        Statistics {
+            effective_candidates : effective_candidates(&ballot_box.totals()),
+            bullet_votes : ballot_box.bullet_votes(),
+            fully_ranked : ballot_box.fully_ranked(),
+            without_first_preferences : ballot_box.without_first_preferences(),
+            total_votes : ballot_box.total_votes(),
+        }
+    }
+}
+
+/// Calculates the Laakso-Taagepera effective number of candidates from first preference totals,
+/// `1 / sum(share^2)`.
+fn effective_candidates(totals : &[u32]) -> Option<f64> {
+    let total : u32 = totals.iter().sum();
+
+    if total == 0 {
+        return None;
+    }
+
+    let sum_of_squares : f64 =
+        totals
+        .iter()
+        .map(|&votes| (f64::from(votes) / f64::from(total)).powi(2))
+        .sum();
+
+    Some(1.0 / sum_of_squares)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ballot::Ballot;
+    use crate::candidates::Candidates;
+
+    #[test]
+    fn totals_match_the_first_preferences_of_the_ballots() {
+        let ballots : Vec<Vec<usize>> = vec![vec![0, 1], vec![2], vec![0], vec![2, 0, 1], vec![0, 2]];
+
+        let mut expected = vec![0; 3];
+        for ballot in &ballots {
+            expected[ballot[0]] += 1;
+        }
+
+        let candidates = Candidates::new(["A", "B", "C"].map(String::from).to_vec());
+        let ballot_box = BallotBox::from_ballots(candidates, ballots.into_iter().map(Ballot::new).collect()).unwrap();
+        let statistics = Statistics::from_ballot_box(&ballot_box);
+
+        assert_eq!(ballot_box.totals(), expected);
+        assert_eq!(statistics.total_votes, expected.iter().sum::<u32>());
+        assert_eq!(statistics.bullet_votes, 2);
+        assert_eq!(statistics.fully_ranked, 1);
+        assert_eq!(statistics.without_first_preferences, 1);
+    }
+}
@@ -0,0 +1,58 @@
+use clap::ValueEnum;
+
+use crate::random::Rng;
+
+/// How to choose between candidates tied on the fewest votes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Tiebreak {
+    /// Eliminate all of the tied candidates together.
+    All,
+    /// Eliminate one of the tied candidates, chosen at random.
+    Random,
+    /// Eliminate the tied candidate lowest in a priority order drawn before the count. Candidates
+    /// tied for the lead are also decided by this order.
+    Order,
+}
+
+impl Tiebreak {
+    /// Chooses which of the `tied` candidates are eliminated. `priority` lists every candidate from
+    /// highest to lowest priority.
+    pub fn eliminate(&self, tied : Vec<usize>, rng : &mut Rng, priority : &[usize]) -> Vec<usize> {
+        match self {
+            Tiebreak::All => tied,
+            // A clear loser needs no draw, which would otherwise mark the seed as used.
+            Tiebreak::Random if tied.len() > 1 => vec![tied[rng.below(tied.len())]],
+            Tiebreak::Random => tied,
+            Tiebreak::Order => {
+                let lowest = priority.iter().rev().find(|c| tied.contains(c)).copied().unwrap_or(tied[0]);
+                vec![lowest]
+            }
+        }
+    }
+
+    /// Chooses which of the candidates tied for the lead is elected, if this tiebreak decides such
+    /// ties at all.
+    pub fn elect(&self, tied : &[usize], priority : &[usize]) -> Option<usize> {
+        match self {
+            Tiebreak::Order => priority.iter().find(|c| tied.contains(c)).copied(),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn random_only_draws_between_tied_candidates() {
+        let mut rng = Rng::from_seed(9);
+
+        assert_eq!(Tiebreak::Random.eliminate(vec![2], &mut rng, &[0, 1, 2]), vec![2]);
+        assert!(!rng.used());
+
+        let eliminated = Tiebreak::Random.eliminate(vec![1, 2], &mut rng, &[0, 1, 2]);
+        assert!(eliminated == vec![1] || eliminated == vec![2]);
+        assert!(rng.used());
+    }
+}
@@ -0,0 +1,51 @@
+use std::thread;
+use std::time::Duration;
+
+use colored::*;
+
+use vote_counter::candidates::Candidates;
+use vote_counter::formatting::{self, Formatter, BAR_WIDTH};
+use vote_counter::messages::Message;
+
+/// How long each round stays on screen before the next is drawn.
+const ROUND_DELAY : Duration = Duration::from_millis(1500);
+
+/// Clears the terminal and draws the standings for a round of counting as a bar chart.
+pub fn draw(round : usize, totals : &[u32], candidates : &Candidates, formatter : &Formatter) {
+    // Clear the screen and move the cursor back to the top left.
+    print!("\x1B[2J\x1B[H");
+
+    println!("{} {}", formatter.text(Message::Round).bright_yellow().bold(), round);
+
+    let total : u32 = totals.iter().sum();
+    let max = totals.iter().copied().max().unwrap_or(0);
+
+    let name_width =
+        (0..candidates.len())
+        .map(|c| formatter.candidate(c, candidates).chars().count())
+        .max()
+        .unwrap_or(0);
+
+    for (candidate, &votes) in totals.iter().enumerate() {
+        let bar = formatting::bar(votes, max);
+
+        // Pad after the bar so the counts line up, since the escape codes for the colour would
+        // throw off the formatter's own padding.
+        let padding = " ".repeat(BAR_WIDTH - bar.chars().count());
+
+        println!(
+            "    {:<width$} {}{} {} ({})",
+            formatter.candidate(candidate, candidates),
+            bar.bright_green(),
+            padding,
+            formatter.number(votes),
+            formatter.percent(votes, total),
+            width = name_width,
+        );
+    }
+}
+
+/// Pauses so the current round can be read before the next is drawn.
+pub fn pause() {
+    thread::sleep(ROUND_DELAY);
+}